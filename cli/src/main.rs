@@ -1,13 +1,16 @@
 #![forbid(unsafe_code)]
 
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use anyhow::{anyhow, Result};
 use clap::{Parser, ValueEnum};
 use tokio::{fs, io::AsyncWriteExt};
 
 use api::rename::Destination;
-use mw_tools::{api, Client};
+use api::report::{Outcome, Report};
+use mw_tools::{api, Client, LoginContinuation, LoginStep, SavedSession};
+
+mod progress;
 
 #[derive(Parser, Debug, PartialEq)]
 enum Subcommand {
@@ -77,16 +80,58 @@ struct Cli {
     #[command(subcommand)]
     command: Subcommand,
 
-    #[arg(short, long, env = "FANDOM_BOT_NAME", hide_env_values = true)]
-    name: String,
-    #[arg(short, long, env = "FANDOM_BOT_PASSWORD", hide_env_values = true)]
-    password: String,
+    #[arg(
+        short,
+        long,
+        env = "FANDOM_BOT_NAME",
+        hide_env_values = true,
+        required_unless_present = "access_token"
+    )]
+    name: Option<String>,
+    #[arg(
+        short,
+        long,
+        env = "FANDOM_BOT_PASSWORD",
+        hide_env_values = true,
+        required_unless_present = "access_token"
+    )]
+    password: Option<String>,
+    /// OAuth 2 access token of an owner-only consumer. Used instead of name and password.
+    #[arg(
+        long,
+        env = "FANDOM_BOT_ACCESS_TOKEN",
+        hide_env_values = true,
+        conflicts_with_all = ["name", "password"]
+    )]
+    access_token: Option<String>,
+    /// Log in via action=clientlogin, which supports main account passwords and two-factor authentication.
+    /// Additional steps will be prompted for on the terminal.
+    #[arg(long, conflicts_with = "access_token")]
+    clientlogin: bool,
+    /// Don't restore or save the login session between runs.
+    #[arg(long)]
+    no_session: bool,
+    /// Do all reads and checks, but only print what would change instead of changing it.
+    #[arg(long)]
+    dry_run: bool,
     #[arg(
         short,
         long,
         default_value = "https://leagueoflegends.fandom.com/de/api.php"
     )]
     url: String,
+    /// User agent to identify the bot with. Should contain a way to contact you.
+    #[arg(long, env = "MW_USER_AGENT")]
+    user_agent: Option<String>,
+    /// Proxy to route all requests through, eg http://proxy:8080
+    #[arg(long)]
+    proxy: Option<String>,
+    /// Timeout in seconds for a whole request.
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Timeout in seconds for establishing a connection.
+    #[arg(long)]
+    connect_timeout: Option<u64>,
 }
 
 #[tokio::main]
@@ -94,15 +139,31 @@ async fn main() -> Result<()> {
     pretty_env_logger::init();
 
     let cli = Cli::parse();
-    let mut client = Client::new(&cli.url)?;
-    client.login(&cli.name, &cli.password).await?;
+    let mut builder = Client::builder(&cli.url).dry_run(cli.dry_run);
+    if let Some(user_agent) = &cli.user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    if let Some(proxy) = &cli.proxy {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(timeout) = cli.timeout {
+        builder = builder.timeout(Duration::from_secs(timeout));
+    }
+    if let Some(timeout) = cli.connect_timeout {
+        builder = builder.connect_timeout(Duration::from_secs(timeout));
+    }
+    let mut client = builder.build()?;
+    client.set_warning_handler(|warning| eprintln!("{}", warning));
+    client.set_dry_run_handler(|change| println!("[dry run] {}", change));
+    client.set_progress(progress::ProgressBars::default());
+    login(&mut client, &cli).await?;
     let client = client;
 
     match cli.command {
         Subcommand::Delete { input } => {
             let contents = fs::read_to_string(input).await?;
             let titles: Vec<&str> = contents.lines().collect();
-            api::delete::delete(&client, &titles, None).await?;
+            print_report(&api::delete::delete(&client, &titles, None).await?)?;
         }
         Subcommand::List {
             list_type,
@@ -166,12 +227,15 @@ async fn main() -> Result<()> {
             } else {
                 Some(Destination::Plain(to))
             };
-            api::rename::rename(&client, from, to, prepend.as_deref(), append.as_deref()).await?;
+            let report =
+                api::rename::rename(&client, from, to, prepend.as_deref(), append.as_deref())
+                    .await?;
+            print_report(&report)?;
         }
         Subcommand::Nulledit { input } => {
             let contents = fs::read_to_string(input).await?;
             let titles: Vec<&str> = contents.lines().collect();
-            api::edit::nulledit(&client, &titles).await?;
+            print_report(&api::edit::nulledit(&client, &titles).await?)?;
         }
         Subcommand::Purge { input, recursive } => {
             let contents = fs::read_to_string(input).await?;
@@ -198,6 +262,141 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Print the result of every title and a summary. Fails if any title failed or got cancelled, for a non-zero exit code.
+fn print_report(report: &Report) -> Result<()> {
+    // Dry-run items were already printed by the dry-run handler.
+    for item in report
+        .items
+        .iter()
+        .filter(|i| !matches!(i.outcome, Outcome::DryRun(_)))
+    {
+        println!("{}", item);
+    }
+    println!("{}", report);
+
+    let failed = report.failed().count();
+    if report.is_success() {
+        Ok(())
+    } else if failed > 0 {
+        Err(anyhow!(
+            "{} of {} titles failed",
+            failed,
+            report.items.len()
+        ))
+    } else {
+        Err(anyhow!(
+            "cancelled before {} of {} titles",
+            report.cancelled().count(),
+            report.items.len()
+        ))
+    }
+}
+
+/// Log in with the given credentials, reusing the session of a previous run if it's still valid.
+async fn login(client: &mut Client, cli: &Cli) -> Result<()> {
+    if let Some(token) = &cli.access_token {
+        return Ok(client.login_oauth(token).await?);
+    }
+    let (Some(name), Some(password)) = (&cli.name, &cli.password) else {
+        return Err(anyhow!(
+            "either name and password or an access token are required"
+        ));
+    };
+
+    let session_key = session_key(&cli.url, name);
+    if !cli.no_session {
+        if let Ok(session) = storage::load_secure::<SavedSession>(&session_key).await {
+            match client.restore_session(&session).await {
+                Ok(true) => {
+                    log::info!("Restored session of previous run");
+                    if !cli.clientlogin {
+                        client.set_credentials(name, password);
+                    }
+                    return Ok(());
+                }
+                Ok(false) => {}
+                // Eg a timeout while validating the session, logging in again might still work.
+                Err(err) => log::warn!("Couldn't restore session of previous run: {}", err),
+            }
+        }
+    }
+
+    if cli.clientlogin {
+        let mut step = client.client_login(name, password).await?;
+        while let LoginStep::Ui(continuation) = step {
+            let answers = prompt_login_step(&continuation)?;
+            let answers: Vec<(&str, &str)> = answers
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect();
+            step = client.continue_login(&answers).await?;
+        }
+    } else {
+        client.login(name, password).await?;
+    }
+
+    if !cli.no_session {
+        if let Some(session) = client.export_session() {
+            storage::save_secure(&session_key, session).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Storage key for the session of an account on a wiki.
+fn session_key(url: &str, name: &str) -> String {
+    format!("session_{}_{}", url, name)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Ask the user for every input field of a pending clientlogin step.
+fn prompt_login_step(continuation: &LoginContinuation) -> Result<Vec<(String, String)>> {
+    println!("{}", continuation.message);
+
+    let mut answers = Vec::new();
+    for (name, field) in continuation.requests.iter().flat_map(|r| &r.fields) {
+        if !field.is_input() {
+            // Informational fields, for example the URL of a captcha image.
+            if let Some(value) = field.value.as_deref().filter(|_| field.kind == "null") {
+                println!("{}: {}", field.label, value);
+            }
+            continue;
+        }
+
+        if !field.help.is_empty() {
+            println!("{}", field.help);
+        }
+        if !field.options.is_empty() {
+            let options: Vec<&str> = field.options.keys().map(String::as_str).collect();
+            println!("Options: {}", options.join(", "));
+        }
+
+        let prompt = format!(
+            "{}{}: ",
+            field.label,
+            if field.optional { " (optional)" } else { "" }
+        );
+        let value = if field.sensitive || field.kind == "password" {
+            rpassword::prompt_password(prompt)?
+        } else {
+            print!("{}", prompt);
+            std::io::Write::flush(&mut std::io::stdout())?;
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+            line.trim().to_string()
+        };
+
+        if !value.is_empty() {
+            answers.push((name.clone(), value));
+        }
+    }
+
+    Ok(answers)
+}
+
 #[test]
 fn verify_cli() {
     use clap::CommandFactory;
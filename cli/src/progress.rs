@@ -0,0 +1,45 @@
+use std::sync::Mutex;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use mw_tools::{Progress, ProgressEvent};
+
+/// Draws a progress bar on stderr for bulk operations. Hidden if stderr isn't a terminal.
+#[derive(Default)]
+pub(crate) struct ProgressBars {
+    bar: Mutex<Option<ProgressBar>>,
+}
+
+impl Progress for ProgressBars {
+    fn on_event(&self, event: &ProgressEvent<'_>) {
+        let mut bar = self.bar.lock().unwrap_or_else(|e| e.into_inner());
+        match event {
+            ProgressEvent::Started { operation, total } => {
+                let new = match total {
+                    Some(total) => ProgressBar::new(*total as u64).with_style(
+                        ProgressStyle::with_template("{prefix} [{bar:40}] {pos}/{len} {wide_msg}")
+                            .expect("valid template")
+                            .progress_chars("=> "),
+                    ),
+                    // Lists don't know their length upfront.
+                    None => ProgressBar::new_spinner().with_style(
+                        ProgressStyle::with_template("{prefix} {spinner} {pos} {wide_msg}")
+                            .expect("valid template"),
+                    ),
+                };
+                new.set_prefix(operation.to_string());
+                *bar = Some(new);
+            }
+            ProgressEvent::ItemDone { title, .. } | ProgressEvent::ItemFailed { title, .. } => {
+                if let Some(bar) = bar.as_ref() {
+                    bar.set_message(title.to_string());
+                    bar.inc(1);
+                }
+            }
+            ProgressEvent::Finished { .. } => {
+                if let Some(bar) = bar.take() {
+                    bar.finish_and_clear();
+                }
+            }
+        }
+    }
+}
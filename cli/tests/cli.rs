@@ -0,0 +1,129 @@
+use std::{path::PathBuf, process::Output};
+
+use mock_wiki::{MockWiki, PASSWORD, USER};
+
+fn mw_cli(wiki: &MockWiki, args: &[&str]) -> Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_mw-cli"))
+        .args(["--url", &wiki.url(), "--name", USER, "--password", PASSWORD])
+        .arg("--no-session")
+        .args(args)
+        .env_remove("FANDOM_BOT_ACCESS_TOKEN")
+        .output()
+        .unwrap()
+}
+
+fn input_file(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mw-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn list() {
+    let wiki = MockWiki::start().await;
+    wiki.set_max_limit(1);
+    wiki.add_page("A", "[[Category:Test]]");
+    wiki.add_page("B", "[[Category:Test]]");
+
+    let output = mw_cli(&wiki, &["list", "categorymembers", "Category:Test"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "A\nB\n");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delete_and_move() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    wiki.add_page("B", "b");
+
+    let delete = input_file("delete.txt", "A\n");
+    assert!(mw_cli(&wiki, &["delete", delete.to_str().unwrap()])
+        .status
+        .success());
+    let rename = input_file("move.txt", "B;C\n");
+    assert!(mw_cli(&wiki, &["move", rename.to_str().unwrap()])
+        .status
+        .success());
+
+    assert_eq!(wiki.titles(), ["B", "C"]);
+    assert_eq!(wiki.page("C").as_deref(), Some("b"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn nulledit_and_purge() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    let input = input_file("titles.txt", "A\n");
+
+    assert!(mw_cli(&wiki, &["nulledit", input.to_str().unwrap()])
+        .status
+        .success());
+    assert!(mw_cli(&wiki, &["purge", input.to_str().unwrap()])
+        .status
+        .success());
+
+    assert_eq!(wiki.revisions("A"), 1);
+    assert_eq!(wiki.purged(), ["A"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn upload() {
+    let wiki = MockWiki::start().await;
+    let file = input_file("Upload.txt", "content");
+
+    let output = mw_cli(&wiki, &["upload", file.to_str().unwrap(), "--text", "desc"]);
+
+    assert!(output.status.success());
+    assert_eq!(wiki.file("Upload.txt").unwrap(), b"content");
+    assert_eq!(wiki.page("File:Upload.txt").as_deref(), Some("desc"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wrong_password() {
+    let wiki = MockWiki::start().await;
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_mw-cli"))
+        .args(["--url", &wiki.url(), "--name", USER, "--password", "wrong"])
+        .args(["--no-session", "list", "allpages"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dry_run() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    let input = input_file("dry-move.txt", "A;B\n");
+
+    let output = mw_cli(&wiki, &["--dry-run", "move", input.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "[dry run] move \"A\": would move to \"B\"\n\
+         0 succeeded, 0 skipped, 0 failed, 1 planned in dry-run mode\n"
+    );
+    assert_eq!(wiki.titles(), ["A"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reports_failed_titles() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    let input = input_file("partial-delete.txt", "A\nMissing\n");
+
+    let output = mw_cli(&wiki, &["delete", input.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("A: done, log entry "));
+    assert!(stdout.contains("Missing: failed, API returned an error: \"missingtitle\""));
+    assert!(stdout.ends_with("1 succeeded, 0 skipped, 1 failed\n"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 of 2 titles failed"));
+    assert!(wiki.titles().is_empty());
+}
@@ -0,0 +1,289 @@
+#![forbid(unsafe_code)]
+
+//! A local HTTP server emulating the parts of the mediawiki API used by mw-tools, backed by an in-memory page store.
+//!
+//! Meant for integration tests, so everything panics instead of returning errors.
+//! # Example
+//! ```no_run
+//! # async fn test_wiki() {
+//! let wiki = mock_wiki::MockWiki::start().await;
+//! wiki.add_page("Main Page", "Hello [[World]]");
+//!
+//! // Point the client under test to this URL.
+//! let url = wiki.url();
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use axum::{
+    extract::{FromRequest, Multipart, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Form, Json, Router,
+};
+use tokio::task::JoinHandle;
+
+use wiki::Wiki;
+
+mod wiki;
+
+/// Name of the account every wiki starts with.
+pub const USER: &str = "MockBot";
+/// Password of [`USER`].
+pub const PASSWORD: &str = "mockpassword";
+/// Rights of [`USER`]. Enough for every api function.
+pub const RIGHTS: &[&str] = &[
+    "read",
+    "edit",
+    "createpage",
+    "bot",
+    "delete",
+    "move",
+    "move-subpages",
+    "suppressredirect",
+    "upload",
+    "reupload",
+    "purge",
+    "apihighlimits",
+];
+
+const SESSION_COOKIE: &str = "mockwiki_session";
+
+/// Longest URL accepted for GET requests, like the default request line limit of Apache.
+pub const MAX_URL_LENGTH: usize = 8190;
+
+/// A running mock wiki. The server shuts down when this gets dropped.
+#[derive(Debug)]
+pub struct MockWiki {
+    addr: SocketAddr,
+    wiki: Arc<Mutex<Wiki>>,
+    server: JoinHandle<()>,
+}
+
+impl MockWiki {
+    /// Start a server on a random local port with the account [`USER`] and no pages.
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("couldn't bind the mock wiki to a local port");
+        let addr = listener.local_addr().unwrap();
+
+        let mut wiki = Wiki::new();
+        wiki.base_url = format!("http://{}", addr);
+        wiki.add_user(USER, PASSWORD, RIGHTS);
+        let wiki = Arc::new(Mutex::new(wiki));
+
+        let router = Router::new()
+            .route("/api.php", get(api).post(api))
+            .route("/files/{name}", get(file))
+            .with_state(wiki.clone());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        Self { addr, wiki, server }
+    }
+
+    /// URL of api.php.
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!("http://{}/api.php", self.addr)
+    }
+
+    fn wiki(&self) -> MutexGuard<'_, Wiki> {
+        lock(&self.wiki)
+    }
+
+    /// Add an account. Anonymous users can only read, edit and purge.
+    pub fn add_user(&self, name: &str, password: &str, rights: &[&str]) {
+        self.wiki().add_user(name, password, rights);
+    }
+
+    /// Allow `user` to authenticate with an OAuth 2 bearer token.
+    pub fn add_access_token(&self, token: &str, user: &str) {
+        self.wiki()
+            .access_tokens
+            .insert(token.to_string(), user.to_string());
+    }
+
+    /// Create a page, or add a revision if it already exists. Returns the revision id.
+    pub fn add_page(&self, title: &str, content: &str) -> u64 {
+        self.wiki().save_page(title, content, USER, "")
+    }
+
+    /// Add a revision by `user` with an edit summary. Returns the revision id.
+    pub fn add_revision(&self, title: &str, content: &str, user: &str, comment: &str) -> u64 {
+        self.wiki().save_page(title, content, user, comment)
+    }
+
+    /// Hide the content of a revision, like revision deletion does.
+    pub fn hide_revision(&self, revid: u64) {
+        let mut wiki = self.wiki();
+        let revision = wiki
+            .pages
+            .values_mut()
+            .flat_map(|p| p.revisions.iter_mut())
+            .find(|r| r.revid == revid)
+            .expect("revision exists");
+        revision.texthidden = true;
+    }
+
+    /// Add a file with its description page.
+    pub fn add_file(&self, name: &str, content: &[u8]) {
+        let mut wiki = self.wiki();
+        wiki.files.insert(wiki::normalize(name), content.to_vec());
+        wiki.save_page(&format!("File:{}", name), "", USER, "");
+    }
+
+    /// Limit the number of items per list request, to test continuation.
+    pub fn set_max_limit(&self, limit: usize) {
+        self.wiki().max_limit = limit;
+    }
+
+    /// Log out every session, like the server would after a while.
+    pub fn expire_sessions(&self) {
+        self.wiki().sessions.clear();
+    }
+
+    /// Current content of a page.
+    #[must_use]
+    pub fn page(&self, title: &str) -> Option<String> {
+        self.wiki()
+            .pages
+            .get(&wiki::normalize(title))
+            .map(|p| p.current().content.clone())
+    }
+
+    /// Number of revisions of a page.
+    #[must_use]
+    pub fn revisions(&self, title: &str) -> usize {
+        self.wiki()
+            .pages
+            .get(&wiki::normalize(title))
+            .map_or(0, |p| p.revisions.len())
+    }
+
+    /// Titles of all pages.
+    #[must_use]
+    pub fn titles(&self) -> Vec<String> {
+        self.wiki().pages.keys().cloned().collect()
+    }
+
+    /// Content of an uploaded file.
+    #[must_use]
+    pub fn file(&self, name: &str) -> Option<Vec<u8>> {
+        self.wiki().files.get(&wiki::normalize(name)).cloned()
+    }
+
+    /// Number of api.php requests handled so far.
+    #[must_use]
+    pub fn requests(&self) -> usize {
+        self.wiki().requests
+    }
+
+    /// Number of successful logins so far.
+    #[must_use]
+    pub fn logins(&self) -> usize {
+        self.wiki().logins
+    }
+
+    /// Titles purged so far, in order.
+    #[must_use]
+    pub fn purged(&self) -> Vec<String> {
+        self.wiki().purged.clone()
+    }
+}
+
+impl Drop for MockWiki {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+fn lock(wiki: &Mutex<Wiki>) -> MutexGuard<'_, Wiki> {
+    wiki.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+async fn api(State(wiki): State<Arc<Mutex<Wiki>>>, request: Request) -> Response {
+    if request.method() == axum::http::Method::GET
+        && request.uri().to_string().len() > MAX_URL_LENGTH
+    {
+        return StatusCode::URI_TOO_LONG.into_response();
+    }
+    let headers = request.headers().clone();
+    let mut params: HashMap<String, String> =
+        Query::<Vec<(String, String)>>::try_from_uri(request.uri())
+            .map(|q| q.0.into_iter().collect())
+            .unwrap_or_default();
+    let mut file = None;
+
+    let is_multipart = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("multipart/form-data"));
+    if is_multipart {
+        let mut multipart = match Multipart::from_request(request, &()).await {
+            Ok(m) => m,
+            Err(err) => return err.into_response(),
+        };
+        while let Ok(Some(field)) = multipart.next_field().await {
+            let name = field.name().unwrap_or_default().to_string();
+            if name == "file" {
+                file = field.bytes().await.ok().map(|b| b.to_vec());
+            } else if let Ok(value) = field.text().await {
+                params.insert(name, value);
+            }
+        }
+    } else if request.method() == axum::http::Method::POST {
+        match Form::<Vec<(String, String)>>::from_request(request, &()).await {
+            Ok(Form(form)) => params.extend(form),
+            Err(err) => return err.into_response(),
+        }
+    }
+
+    let request = wiki::Request {
+        params,
+        session: session(&headers),
+        bearer: headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(ToString::to_string),
+        file,
+    };
+
+    let reply = lock(&wiki).handle(&request);
+
+    let mut response = Json(reply.body).into_response();
+    if let Some(session) = reply.set_session {
+        let cookie = format!("{}={}; Path=/; HttpOnly", SESSION_COOKIE, session);
+        response
+            .headers_mut()
+            .insert(header::SET_COOKIE, cookie.parse().unwrap());
+    }
+    response
+}
+
+async fn file(State(wiki): State<Arc<Mutex<Wiki>>>, Path(name): Path<String>) -> Response {
+    match lock(&wiki).files.get(&wiki::normalize(&name)) {
+        Some(content) => content.clone().into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn session(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split("; "))
+        .filter_map(|c| c.split_once('='))
+        .find(|(name, value)| *name == SESSION_COOKIE && !value.is_empty())
+        .map(|(_, value)| value.to_string())
+}
@@ -0,0 +1,1199 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{json, Value};
+
+pub(crate) const LOGIN_TOKEN: &str = "mocklogintoken+\\";
+const ANON_CSRF_TOKEN: &str = "+\\";
+
+// (id, localized name, canonical name)
+const NAMESPACES: &[(i32, &str, &str)] = &[
+    (-2, "Media", "Media"),
+    (-1, "Special", "Special"),
+    (0, "", ""),
+    (1, "Talk", "Talk"),
+    (2, "User", "User"),
+    (4, "Project", "Project"),
+    (6, "File", "File"),
+    (10, "Template", "Template"),
+    (14, "Category", "Category"),
+];
+
+/// Rights of anonymous users.
+const ANON_RIGHTS: &[&str] = &["read", "edit", "createpage", "purge"];
+
+#[derive(Clone, Debug)]
+pub(crate) struct Page {
+    pub(crate) pageid: u64,
+    pub(crate) revisions: Vec<Revision>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Revision {
+    pub(crate) revid: u64,
+    pub(crate) parentid: u64,
+    pub(crate) user: String,
+    pub(crate) timestamp: String,
+    pub(crate) comment: String,
+    pub(crate) content: String,
+    // Whether the content got hidden via revision deletion.
+    pub(crate) texthidden: bool,
+}
+
+impl Page {
+    pub(crate) fn current(&self) -> &Revision {
+        self.revisions.last().expect("pages always have a revision")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct User {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) password: String,
+    pub(crate) rights: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Wiki {
+    pub(crate) base_url: String,
+    pub(crate) pages: BTreeMap<String, Page>,
+    pub(crate) files: BTreeMap<String, Vec<u8>>,
+    pub(crate) users: Vec<User>,
+    pub(crate) access_tokens: HashMap<String, String>,
+    // session id -> user name
+    pub(crate) sessions: HashMap<String, String>,
+    pub(crate) purged: Vec<String>,
+    // title -> timestamp of the deletion
+    deleted: HashMap<String, String>,
+    pub(crate) max_limit: usize,
+    pub(crate) requests: usize,
+    pub(crate) logins: usize,
+    next_id: u64,
+}
+
+/// A parsed request to api.php.
+#[derive(Debug, Default)]
+pub(crate) struct Request {
+    pub(crate) params: HashMap<String, String>,
+    pub(crate) session: Option<String>,
+    pub(crate) bearer: Option<String>,
+    pub(crate) file: Option<Vec<u8>>,
+}
+
+impl Request {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    fn list(&self, name: &str) -> Vec<&str> {
+        self.param(name)
+            .map(|v| v.split('|').filter(|v| !v.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.params.contains_key(name)
+    }
+}
+
+/// The json body and the new session cookie, if it changed.
+#[derive(Debug)]
+pub(crate) struct Reply {
+    pub(crate) body: Value,
+    pub(crate) set_session: Option<String>,
+}
+
+type Result<T = Value> = std::result::Result<T, Value>;
+
+fn error(code: &str, text: &str) -> Value {
+    json!({ "errors": [{ "code": code, "text": text, "module": "main" }] })
+}
+
+impl Wiki {
+    pub(crate) fn new() -> Self {
+        Self {
+            max_limit: 500,
+            ..Self::default()
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    // Deterministic timestamps, one second apart.
+    fn timestamp(id: u64) -> String {
+        format!(
+            "2024-01-01T{:02}:{:02}:{:02}Z",
+            id / 3600 % 24,
+            id / 60 % 60,
+            id % 60
+        )
+    }
+
+    pub(crate) fn add_user(&mut self, name: &str, password: &str, rights: &[&str]) {
+        let id = self.next_id();
+        self.users.push(User {
+            id,
+            name: name.to_string(),
+            password: password.to_string(),
+            rights: rights.iter().map(ToString::to_string).collect(),
+        });
+    }
+
+    pub(crate) fn save_page(
+        &mut self,
+        title: &str,
+        content: &str,
+        user: &str,
+        comment: &str,
+    ) -> u64 {
+        let revid = self.next_id();
+        let title = normalize(title);
+        let revision = Revision {
+            revid,
+            parentid: 0,
+            user: user.to_string(),
+            timestamp: Self::timestamp(revid),
+            comment: comment.to_string(),
+            content: content.to_string(),
+            texthidden: false,
+        };
+        match self.pages.get_mut(&title) {
+            Some(page) => {
+                let parentid = page.current().revid;
+                page.revisions.push(Revision {
+                    parentid,
+                    ..revision
+                });
+            }
+            None => {
+                let pageid = self.next_id();
+                self.pages.insert(
+                    title,
+                    Page {
+                        pageid,
+                        revisions: vec![revision],
+                    },
+                );
+            }
+        }
+        revid
+    }
+
+    pub(crate) fn handle(&mut self, request: &Request) -> Reply {
+        self.requests += 1;
+        let mut set_session = None;
+        let body = match self.dispatch(request, &mut set_session) {
+            Ok(body) | Err(body) => body,
+        };
+        Reply { body, set_session }
+    }
+
+    fn user(&self, request: &Request) -> Option<&User> {
+        let name = match &request.bearer {
+            Some(token) => self.access_tokens.get(token)?,
+            None => self.sessions.get(request.session.as_ref()?)?,
+        };
+        self.users.iter().find(|u| &u.name == name)
+    }
+
+    fn rights(&self, request: &Request) -> Vec<String> {
+        match self.user(request) {
+            Some(user) => user.rights.clone(),
+            None => ANON_RIGHTS.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn csrf_token(&self, request: &Request) -> String {
+        match self.user(request) {
+            Some(user) => format!(
+                "csrf{}{}+\\",
+                user.id,
+                request.session.as_deref().unwrap_or("")
+            ),
+            None => ANON_CSRF_TOKEN.to_string(),
+        }
+    }
+
+    fn check_token(&self, request: &Request) -> Result<()> {
+        match request.param("token") {
+            None => Err(error(
+                "missingparam",
+                "The \"token\" parameter must be set.",
+            )),
+            Some(token) if token != self.csrf_token(request) => {
+                Err(error("badtoken", "Invalid CSRF token."))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn check_right(&self, request: &Request, right: &str) -> Result<()> {
+        if self.rights(request).iter().any(|r| r == right) {
+            Ok(())
+        } else {
+            Err(error(
+                "permissiondenied",
+                &format!("You don't have permission to use the \"{}\" right.", right),
+            ))
+        }
+    }
+
+    // Multi-value parameters are limited to 50 values, or 500 with apihighlimits.
+    fn check_values(&self, request: &Request, name: &str) -> Result<()> {
+        let limit = if self.rights(request).iter().any(|r| r == "apihighlimits") {
+            500
+        } else {
+            50
+        };
+        if request.list(name).len() > limit {
+            return Err(error(
+                "toomanyvalues",
+                &format!(
+                    "Too many values supplied for parameter \"{}\". The limit is {}.",
+                    name, limit
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn user_name(&self, request: &Request) -> String {
+        self.user(request)
+            .map_or_else(|| "127.0.0.1".to_string(), |u| u.name.clone())
+    }
+
+    fn dispatch(&mut self, request: &Request, set_session: &mut Option<String>) -> Result {
+        if request.param("assert") == Some("user") && self.user(request).is_none() {
+            return Err(error(
+                "assertuserfailed",
+                "You are no longer logged in, so the action could not be completed.",
+            ));
+        }
+        self.check_values(request, "titles")?;
+        self.check_values(request, "pageids")?;
+        self.check_values(request, "revids")?;
+
+        match request.param("action").unwrap_or_default() {
+            "query" => self.query(request),
+            "login" => self.login(request, set_session),
+            "clientlogin" => self.client_login(request, set_session),
+            "logout" => {
+                self.check_token(request)?;
+                if let Some(session) = &request.session {
+                    self.sessions.remove(session);
+                }
+                *set_session = Some(String::new());
+                Ok(json!({}))
+            }
+            "edit" => self.edit(request),
+            "move" => self.rename(request),
+            "delete" => self.delete(request),
+            "upload" => self.upload(request),
+            "purge" => self.purge(request),
+            "parse" => self.parse(request),
+            "compare" => self.compare(request),
+            other => Err(error(
+                "badvalue",
+                &format!("Unrecognized value for parameter \"action\": {}.", other),
+            )),
+        }
+    }
+
+    fn start_session(&mut self, user: &str, set_session: &mut Option<String>) -> u64 {
+        self.logins += 1;
+        let id = self.next_id();
+        let session = format!("s{}", id);
+        self.sessions.insert(session.clone(), user.to_string());
+        *set_session = Some(session);
+        id
+    }
+
+    fn check_credentials(&self, name: &str, password: &str) -> Option<User> {
+        self.users
+            .iter()
+            .find(|u| u.name == name && u.password == password)
+            .cloned()
+    }
+
+    fn login(&mut self, request: &Request, set_session: &mut Option<String>) -> Result {
+        if request.param("lgtoken") != Some(LOGIN_TOKEN) {
+            return Ok(json!({ "login": { "result": "Failed", "reason": {
+                "code": "sessionfailure",
+                "text": "There seems to be a problem with your login session."
+            } } }));
+        }
+        let name = request.param("lgname").unwrap_or_default();
+        let password = request.param("lgpassword").unwrap_or_default();
+        match self.check_credentials(name, password) {
+            Some(user) => {
+                self.start_session(&user.name, set_session);
+                Ok(json!({ "login": {
+                    "result": "Success",
+                    "lguserid": user.id,
+                    "lgusername": user.name,
+                } }))
+            }
+            None => Ok(json!({ "login": { "result": "Failed", "reason": {
+                "code": "wrongpassword",
+                "text": "Incorrect username or password entered. Please try again."
+            } } })),
+        }
+    }
+
+    fn client_login(&mut self, request: &Request, set_session: &mut Option<String>) -> Result {
+        if request.param("logintoken") != Some(LOGIN_TOKEN) {
+            return Err(error("badtoken", "Invalid token."));
+        }
+        let name = request.param("username").unwrap_or_default();
+        let password = request.param("password").unwrap_or_default();
+        match self.check_credentials(name, password) {
+            Some(user) => {
+                self.start_session(&user.name, set_session);
+                Ok(json!({ "clientlogin": { "status": "PASS", "username": user.name } }))
+            }
+            None => Ok(json!({ "clientlogin": {
+                "status": "FAIL",
+                "message": "Incorrect username or password entered. Please try again.",
+                "messagecode": "wrongpassword",
+            } })),
+        }
+    }
+
+    fn query(&self, request: &Request) -> Result {
+        let mut query = serde_json::Map::new();
+        let mut continuation = None;
+
+        for meta in request.list("meta") {
+            match meta {
+                "tokens" => {
+                    let mut tokens = serde_json::Map::new();
+                    for kind in request.list("type") {
+                        match kind {
+                            "login" => tokens.insert("logintoken".into(), LOGIN_TOKEN.into()),
+                            "csrf" => {
+                                tokens.insert("csrftoken".into(), self.csrf_token(request).into())
+                            }
+                            _ => None,
+                        };
+                    }
+                    query.insert("tokens".into(), tokens.into());
+                }
+                "siteinfo" => {
+                    query.extend(self.siteinfo());
+                }
+                "userinfo" => {
+                    let user = self.user(request);
+                    query.insert(
+                        "userinfo".into(),
+                        json!({
+                            "id": user.map_or(0, |u| u.id),
+                            "name": self.user_name(request),
+                            "anon": user.is_none(),
+                            "groups": if user.is_some() { vec!["*", "user"] } else { vec!["*"] },
+                            "rights": self.rights(request),
+                            "ratelimits": {},
+                        }),
+                    );
+                }
+                other => return Err(unrecognized("meta", other)),
+            }
+        }
+
+        for list in request.list("list") {
+            let (items, next) = self.list(request, list)?;
+            query.insert(list.into(), items.into());
+            if next.is_some() {
+                continuation = next;
+            }
+        }
+
+        let titles = request.list("titles");
+        if titles.len() > 1 && HISTORY_PARAMS.iter().any(|p| request.flag(p)) {
+            return Err(error(
+                "multpages",
+                "titles, pageids or a generator was used to supply multiple pages, but the rvlimit, rvdir, rvuser and rvexcludeuser parameters may only be used on a single page.",
+            ));
+        }
+        if !titles.is_empty() {
+            let (pages, normalized, redirects) = self.pages(request, &titles, &mut continuation);
+            query.insert("pages".into(), pages.into());
+            if !normalized.is_empty() {
+                query.insert("normalized".into(), normalized.into());
+            }
+            if !redirects.is_empty() {
+                query.insert("redirects".into(), redirects.into());
+            }
+        }
+
+        let revids = request.list("revids");
+        if !revids.is_empty() {
+            let (pages, badrevids) = self.revisions_by_id(request, &revids);
+            query.insert("pages".into(), pages.into());
+            if !badrevids.is_empty() {
+                query.insert("badrevids".into(), badrevids.into());
+            }
+        }
+
+        let mut body = json!({ "batchcomplete": continuation.is_none(), "query": query });
+        if let Some(continuation) = continuation {
+            body["continue"] = continuation;
+        }
+        if request.flag("curtimestamp") {
+            body["curtimestamp"] = Self::timestamp(self.next_id).into();
+        }
+        Ok(body)
+    }
+
+    fn siteinfo(&self) -> serde_json::Map<String, Value> {
+        let namespaces: serde_json::Map<String, Value> = NAMESPACES
+            .iter()
+            .map(|(id, name, canonical)| {
+                let mut ns = json!({
+                    "id": id,
+                    "case": "first-letter",
+                    "name": name,
+                    "subpages": false,
+                    "content": *id == 0,
+                    "nonincludable": false,
+                });
+                if *id != 0 {
+                    ns["canonical"] = json!(canonical);
+                }
+                (id.to_string(), ns)
+            })
+            .collect();
+
+        let mut map = serde_json::Map::new();
+        map.insert(
+            "general".into(),
+            json!({
+                "sitename": "Mock Wiki",
+                "generator": "MediaWiki 1.39.0",
+                "case": "first-letter",
+                "lang": "en",
+                "server": self.base_url,
+            }),
+        );
+        map.insert("namespaces".into(), namespaces.into());
+        map.insert(
+            "namespacealiases".into(),
+            json!([{ "id": 6, "alias": "Image" }]),
+        );
+        map.insert("extensions".into(), json!([]));
+        map.insert("magicwords".into(), json!([]));
+        map
+    }
+
+    // Items of a list module and the continuation, if there are more.
+    fn list(&self, request: &Request, list: &str) -> Result<(Vec<Value>, Option<Value>)> {
+        let (prefix, items): (&str, Vec<Value>) = match list {
+            "allpages" => {
+                let ns = request
+                    .param("apnamespace")
+                    .and_then(|ns| ns.parse().ok())
+                    .unwrap_or(0);
+                ("ap", self.titles_where(|title, _| namespace(title) == ns))
+            }
+            "allimages" => (
+                "ai",
+                self.files
+                    .keys()
+                    .map(|name| json!({ "name": name, "ns": 6, "title": format!("File:{}", name) }))
+                    .collect(),
+            ),
+            "allcategories" => {
+                let mut categories: Vec<String> = self
+                    .pages
+                    .values()
+                    .flat_map(|p| links(&p.current().content, "Category:"))
+                    .collect();
+                categories.sort();
+                categories.dedup();
+                (
+                    "ac",
+                    categories
+                        .into_iter()
+                        .map(|c| json!({ "category": c }))
+                        .collect(),
+                )
+            }
+            "alllinks" => {
+                let mut targets: Vec<String> = self
+                    .pages
+                    .values()
+                    .flat_map(|p| links(&p.current().content, ""))
+                    .collect();
+                targets.sort();
+                targets.dedup();
+                (
+                    "al",
+                    targets
+                        .into_iter()
+                        .map(|t| json!({ "ns": namespace(&t), "title": t }))
+                        .collect(),
+                )
+            }
+            "categorymembers" => {
+                let category = required(request, "cmtitle")?;
+                let name = normalize(category.trim_start_matches("Category:"));
+                (
+                    "cm",
+                    self.titles_where(|_, content| links(content, "Category:").contains(&name)),
+                )
+            }
+            "backlinks" => {
+                let target = normalize(required(request, "bltitle")?);
+                (
+                    "bl",
+                    self.titles_where(|_, content| links(content, "").contains(&target)),
+                )
+            }
+            "embeddedin" => {
+                let template = normalize(required(request, "eititle")?);
+                (
+                    "ei",
+                    self.titles_where(|_, content| templates(content).contains(&template)),
+                )
+            }
+            "imageusage" => {
+                let file = normalize(required(request, "iutitle")?);
+                let file = file.trim_start_matches("File:");
+                (
+                    "iu",
+                    self.titles_where(|_, content| {
+                        links(content, "File:").iter().any(|f| f == file)
+                    }),
+                )
+            }
+            "search" => {
+                let search = required(request, "srsearch")?.to_lowercase();
+                (
+                    "sr",
+                    self.titles_where(|title, content| {
+                        title.to_lowercase().contains(&search)
+                            || content.to_lowercase().contains(&search)
+                    }),
+                )
+            }
+            other => return Err(unrecognized("list", other)),
+        };
+
+        let offset: usize = request
+            .param(&format!("{}continue", prefix))
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(0);
+        let limit = match request.param(&format!("{}limit", prefix)) {
+            Some("max") => self.max_limit,
+            Some(limit) => limit.parse().unwrap_or(10).min(self.max_limit),
+            None => 10.min(self.max_limit),
+        };
+
+        let total = items.len();
+        let items: Vec<Value> = items.into_iter().skip(offset).take(limit).collect();
+        let next = (offset + limit < total).then(|| {
+            json!({
+                format!("{}continue", prefix): (offset + limit).to_string(),
+                "continue": "-||",
+            })
+        });
+
+        Ok((items, next))
+    }
+
+    fn titles_where(&self, filter: impl Fn(&str, &str) -> bool) -> Vec<Value> {
+        self.pages
+            .iter()
+            .filter(|(title, page)| filter(title, &page.current().content))
+            .map(|(title, page)| json!({ "pageid": page.pageid, "ns": namespace(title), "title": title }))
+            .collect()
+    }
+
+    fn pages(
+        &self,
+        request: &Request,
+        titles: &[&str],
+        continuation: &mut Option<Value>,
+    ) -> (Vec<Value>, Vec<Value>, Vec<Value>) {
+        let props = request.list("prop");
+        let mut pages = Vec::new();
+        let mut normalized = Vec::new();
+        let mut redirects = Vec::new();
+
+        for title in titles {
+            if let Some(c) = title.chars().find(|c| "<>[]{}|#".contains(*c)) {
+                pages.push(json!({
+                    "title": title,
+                    "invalidreason": format!("The requested page title contains invalid characters: \"{c}\"."),
+                    "invalid": true,
+                }));
+                continue;
+            }
+
+            let mut normal = normalize(title);
+            if normal != *title {
+                normalized.push(json!({ "from": title, "to": normal }));
+            }
+            if request.flag("redirects") {
+                if let Some(target) = self
+                    .pages
+                    .get(&normal)
+                    .and_then(|p| redirect(&p.current().content))
+                {
+                    redirects.push(json!({ "from": normal, "to": target }));
+                    normal = target;
+                }
+            }
+
+            let Some(page) = self.pages.get(&normal) else {
+                pages.push(json!({ "ns": namespace(&normal), "title": normal, "missing": true }));
+                continue;
+            };
+
+            let mut value =
+                json!({ "pageid": page.pageid, "ns": namespace(&normal), "title": normal });
+            if props.contains(&"revisions") {
+                if HISTORY_PARAMS.iter().any(|p| request.flag(p)) {
+                    let (revisions, next) = history(request, page, self.max_limit);
+                    value["revisions"] = revisions.into();
+                    if next.is_some() {
+                        *continuation = next;
+                    }
+                } else {
+                    value["revisions"] = json!([revision_json(request, page.current())]);
+                }
+            }
+            if props.contains(&"imageinfo") {
+                if let Some(name) = normal.strip_prefix("File:") {
+                    if let Some(file) = self.files.get(name) {
+                        value["imageinfo"] = json!([{
+                            "url": format!("{}/files/{}", self.base_url, name),
+                            "descriptionurl": format!("{}/wiki/{}", self.base_url, normal),
+                            "size": file.len(),
+                        }]);
+                    }
+                }
+            }
+            pages.push(value);
+        }
+
+        (pages, normalized, redirects)
+    }
+
+    // Pages with the revisions of `revids`, and the ids that don't exist.
+    fn revisions_by_id(
+        &self,
+        request: &Request,
+        revids: &[&str],
+    ) -> (Vec<Value>, serde_json::Map<String, Value>) {
+        let mut pages: Vec<Value> = Vec::new();
+        let mut badrevids = serde_json::Map::new();
+
+        for revid in revids {
+            let found = self.pages.iter().find_map(|(title, page)| {
+                let revision = page
+                    .revisions
+                    .iter()
+                    .find(|r| r.revid.to_string() == *revid)?;
+                Some((title, page, revision))
+            });
+            let Some((title, page, revision)) = found else {
+                badrevids.insert(
+                    revid.to_string(),
+                    json!({ "revid": revid.parse::<u64>().ok(), "missing": true }),
+                );
+                continue;
+            };
+            let revision = revision_json(request, revision);
+            match pages.iter_mut().find(|p| p["pageid"] == page.pageid) {
+                Some(existing) => existing["revisions"]
+                    .as_array_mut()
+                    .expect("revisions are an array")
+                    .push(revision),
+                None => pages.push(json!({
+                    "pageid": page.pageid,
+                    "ns": namespace(title),
+                    "title": title,
+                    "revisions": [revision],
+                })),
+            }
+        }
+
+        (pages, badrevids)
+    }
+
+    fn compare(&self, request: &Request) -> Result {
+        let (from, from_text) = self.compare_side(request, "from")?;
+        let (to, to_text) = self.compare_side(request, "to")?;
+
+        let mut compare = json!({ "body": diff_rows(&from_text, &to_text) });
+        if let Some((revid, title)) = from {
+            compare["fromrevid"] = revid.into();
+            compare["fromtitle"] = title.into();
+        }
+        if let Some((revid, title)) = to {
+            compare["torevid"] = revid.into();
+            compare["totitle"] = title.into();
+        }
+        Ok(json!({ "compare": compare }))
+    }
+
+    // The revision id and title of one side of action=compare, if it isn't a text, and its content.
+    fn compare_side(
+        &self,
+        request: &Request,
+        prefix: &str,
+    ) -> Result<(Option<(u64, String)>, String)> {
+        if let Some(revid) = request.param(&format!("{}rev", prefix)) {
+            let found = self.pages.iter().find_map(|(title, page)| {
+                let revision = page
+                    .revisions
+                    .iter()
+                    .find(|r| r.revid.to_string() == revid)?;
+                Some((title, revision))
+            });
+            return match found {
+                Some((title, revision)) => Ok((
+                    Some((revision.revid, title.clone())),
+                    revision.content.clone(),
+                )),
+                None => Err(error(
+                    "nosuchrevid",
+                    &format!("There is no revision with ID {}.", revid),
+                )),
+            };
+        }
+        if let Some(title) = request.param(&format!("{}title", prefix)) {
+            let title = normalize(title);
+            return match self.pages.get(&title) {
+                Some(page) => Ok((
+                    Some((page.current().revid, title)),
+                    page.current().content.clone(),
+                )),
+                None => Err(error(
+                    "missingtitle",
+                    "The page you specified doesn't exist.",
+                )),
+            };
+        }
+        match request.param(&format!("{}text-main", prefix)) {
+            Some(text) => Ok((None, text.to_string())),
+            None => Err(error(
+                "missingparam",
+                &format!(
+                    "One of the parameters \"{0}title\", \"{0}rev\" and \"{0}text-main\" is required.",
+                    prefix
+                ),
+            )),
+        }
+    }
+
+    fn edit(&mut self, request: &Request) -> Result {
+        self.check_token(request)?;
+        self.check_right(request, "edit")?;
+        let title = normalize(required(request, "title")?);
+        let user = self.user_name(request);
+
+        let current = self.pages.get(&title).map(|p| p.current().clone());
+        if current.is_none() && request.flag("nocreate") {
+            return Err(error(
+                "missingtitle",
+                "The page you specified doesn't exist.",
+            ));
+        }
+        if current.is_some() && request.flag("createonly") {
+            return Err(error(
+                "articleexists",
+                "The article you tried to create has been created already.",
+            ));
+        }
+        if let Some(md5) = request.param("md5") {
+            let sent = match request.param("text") {
+                Some(text) => text.to_string(),
+                None => format!(
+                    "{}{}",
+                    request.param("prependtext").unwrap_or_default(),
+                    request.param("appendtext").unwrap_or_default()
+                ),
+            };
+            if format!("{:x}", md5::compute(sent)) != md5 {
+                return Err(error("badmd5", "The supplied MD5 hash was incorrect."));
+            }
+        }
+        if let (Some(current), Some(base)) = (&current, request.param("basetimestamp")) {
+            if current.timestamp.as_str() > base {
+                return Err(error("editconflict", "Edit conflict."));
+            }
+        }
+        if let (None, Some(start), false) = (
+            &current,
+            request.param("starttimestamp"),
+            request.flag("recreate"),
+        ) {
+            if self
+                .deleted
+                .get(&title)
+                .is_some_and(|deleted| deleted.as_str() > start)
+            {
+                return Err(error(
+                    "pagedeleted",
+                    "The page has been deleted since you fetched its timestamp.",
+                ));
+            }
+        }
+
+        let old = current
+            .as_ref()
+            .map(|r| r.content.clone())
+            .unwrap_or_default();
+        let content = match (request.param("text"), request.param("section")) {
+            // Only new sections are supported, not editing existing ones.
+            (Some(text), Some("new")) => {
+                let heading = request.param("sectiontitle").unwrap_or_default();
+                let section = format!("== {} ==\n\n{}", heading, text);
+                if old.is_empty() {
+                    section
+                } else {
+                    format!("{}\n\n{}", old, section)
+                }
+            }
+            (Some(_), Some(section)) => return Err(unrecognized("section", section)),
+            (Some(text), None) => text.to_string(),
+            (None, _) => format!(
+                "{}{}{}",
+                request.param("prependtext").unwrap_or_default(),
+                old,
+                request.param("appendtext").unwrap_or_default()
+            ),
+        };
+
+        let summary = request.param("summary").unwrap_or_default();
+        if current.as_ref().is_some_and(|r| r.content == content) {
+            let pageid = self.pages[&title].pageid;
+            return Ok(json!({ "edit": {
+                "result": "Success",
+                "pageid": pageid,
+                "title": title,
+                "contentmodel": "wikitext",
+                "nochange": true,
+            } }));
+        }
+
+        let newrevid = self.save_page(&title, &content, &user, summary);
+        let page = &self.pages[&title];
+        Ok(json!({ "edit": {
+            "result": "Success",
+            "pageid": page.pageid,
+            "title": title,
+            "contentmodel": "wikitext",
+            "new": current.is_none(),
+            "oldrevid": current.map_or(0, |r| r.revid),
+            "newrevid": newrevid,
+            "newtimestamp": page.current().timestamp,
+        } }))
+    }
+
+    fn rename(&mut self, request: &Request) -> Result {
+        self.check_token(request)?;
+        self.check_right(request, "move")?;
+        if request.flag("movesubpages") {
+            self.check_right(request, "move-subpages")?;
+        }
+        if request.flag("noredirect") {
+            self.check_right(request, "suppressredirect")?;
+        }
+        let from = normalize(required(request, "from")?);
+        let to = normalize(required(request, "to")?);
+        if from == to {
+            return Err(error(
+                "selfmove",
+                "The title is the same; cannot move a page over itself.",
+            ));
+        }
+        if self.pages.contains_key(&to) {
+            return Err(error(
+                "articleexists",
+                "A page of that name already exists, or the name you have chosen is not valid.",
+            ));
+        }
+        let page = self
+            .pages
+            .remove(&from)
+            .ok_or_else(|| error("missingtitle", "The page you specified doesn't exist."))?;
+        self.pages.insert(to.clone(), page);
+
+        let reason = request.param("reason").unwrap_or_default();
+        if !request.flag("noredirect") {
+            let user = self.user_name(request);
+            self.save_page(&from, &format!("#REDIRECT [[{}]]", to), &user, reason);
+        }
+
+        Ok(json!({ "move": { "from": from, "to": to, "reason": reason } }))
+    }
+
+    fn delete(&mut self, request: &Request) -> Result {
+        self.check_token(request)?;
+        self.check_right(request, "delete")?;
+        let title = normalize(required(request, "title")?);
+        if self.pages.remove(&title).is_none() {
+            return Err(error(
+                "missingtitle",
+                "The page you specified doesn't exist.",
+            ));
+        }
+        let logid = self.next_id();
+        self.deleted.insert(title.clone(), Self::timestamp(logid));
+
+        Ok(json!({ "delete": {
+            "title": title,
+            "reason": request.param("reason").unwrap_or_default(),
+            "logid": logid,
+        } }))
+    }
+
+    fn upload(&mut self, request: &Request) -> Result {
+        self.check_token(request)?;
+        self.check_right(request, "upload")?;
+        let filename = normalize(required(request, "filename")?);
+        let file = request.file.clone().ok_or_else(|| {
+            error(
+                "missingparam",
+                "One of the parameters \"filekey\", \"file\" and \"url\" is required.",
+            )
+        })?;
+        let title = format!("File:{}", filename);
+        if self.files.contains_key(&filename) && !request.flag("ignorewarnings") {
+            return Ok(json!({ "upload": {
+                "result": "Warning",
+                "filename": filename,
+                "warnings": { "exists": filename },
+            } }));
+        }
+
+        self.files.insert(filename.clone(), file);
+        if !self.pages.contains_key(&title) {
+            let user = self.user_name(request);
+            self.save_page(&title, request.param("text").unwrap_or_default(), &user, "");
+        }
+
+        Ok(json!({ "upload": { "result": "Success", "filename": filename } }))
+    }
+
+    fn purge(&mut self, request: &Request) -> Result {
+        let mut purged = Vec::new();
+        for title in request.list("titles") {
+            let title = normalize(title);
+            let mut value = json!({ "ns": namespace(&title), "title": title });
+            if self.pages.contains_key(&title) {
+                value["purged"] = true.into();
+                self.purged.push(title);
+            } else {
+                value["missing"] = true.into();
+            }
+            purged.push(value);
+        }
+
+        Ok(json!({ "batchcomplete": true, "purge": purged }))
+    }
+
+    fn parse(&self, request: &Request) -> Result {
+        let title = normalize(required(request, "page")?);
+        let page = self
+            .pages
+            .get(&title)
+            .ok_or_else(|| error("missingtitle", "The page you specified doesn't exist."))?;
+
+        Ok(json!({ "parse": {
+            "title": title,
+            "pageid": page.pageid,
+            "revid": page.current().revid,
+            "wikitext": page.current().content,
+        } }))
+    }
+}
+
+fn required<'a>(request: &'a Request, name: &str) -> Result<&'a str> {
+    request.param(name).ok_or_else(|| {
+        error(
+            "missingparam",
+            &format!("The \"{}\" parameter must be set.", name),
+        )
+    })
+}
+
+fn unrecognized(parameter: &str, value: &str) -> Value {
+    error(
+        "badvalue",
+        &format!(
+            "Unrecognized value for parameter \"{}\": {}.",
+            parameter, value
+        ),
+    )
+}
+
+/// Underscores to spaces and an uppercase first letter, in the title and after the namespace.
+pub(crate) fn normalize(title: &str) -> String {
+    let title = title.trim().replace('_', " ");
+    let (prefix, rest) = match title.split_once(':') {
+        Some((prefix, rest))
+            if NAMESPACES
+                .iter()
+                .any(|(id, name, _)| *id != 0 && name.eq_ignore_ascii_case(prefix.trim())) =>
+        {
+            (Some(ucfirst(prefix.trim())), rest.trim())
+        }
+        _ => (None, title.as_str()),
+    };
+    match prefix {
+        Some(prefix) => format!("{}:{}", prefix, ucfirst(rest)),
+        None => ucfirst(rest),
+    }
+}
+
+fn ucfirst(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn namespace(title: &str) -> i32 {
+    title
+        .split_once(':')
+        .and_then(|(prefix, _)| NAMESPACES.iter().find(|(_, name, _)| *name == prefix))
+        .map_or(0, |(id, _, _)| *id)
+}
+
+// Parameters of prop=revisions which list the history of a single page.
+const HISTORY_PARAMS: [&str; 4] = ["rvlimit", "rvdir", "rvuser", "rvexcludeuser"];
+
+// A revision as returned by prop=revisions. The content is left out if `rvprop` doesn't ask for it, or if it's hidden.
+fn revision_json(request: &Request, revision: &Revision) -> Value {
+    let mut value = json!({
+        "revid": revision.revid,
+        "parentid": revision.parentid,
+        "user": revision.user,
+        "timestamp": revision.timestamp,
+        "size": revision.content.len(),
+        "comment": revision.comment,
+        "tags": [],
+    });
+    if revision.texthidden {
+        value["texthidden"] = json!(true);
+    }
+    let rvprop = request.list("rvprop");
+    if rvprop.is_empty() || rvprop.contains(&"content") {
+        let mut slot = json!({
+            "contentmodel": "wikitext",
+            "contentformat": "text/x-wiki",
+        });
+        if revision.texthidden {
+            slot["texthidden"] = json!(true);
+        } else {
+            slot["content"] = json!(revision.content);
+        }
+        value["slots"] = json!({ "main": slot });
+    }
+    value
+}
+
+// One batch of the history of `page`, newest first unless `rvdir=newer`.
+fn history(request: &Request, page: &Page, max_limit: usize) -> (Vec<Value>, Option<Value>) {
+    let mut revisions: Vec<&Revision> = page
+        .revisions
+        .iter()
+        .filter(|r| request.param("rvuser").is_none_or(|u| r.user == u))
+        .filter(|r| request.param("rvexcludeuser").is_none_or(|u| r.user != u))
+        .collect();
+    if request.param("rvdir") != Some("newer") {
+        revisions.reverse();
+    }
+
+    let offset: usize = request
+        .param("rvcontinue")
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(0);
+    let limit = match request.param("rvlimit") {
+        Some("max") => max_limit,
+        Some(limit) => limit.parse().unwrap_or(1).min(max_limit),
+        None => 1,
+    };
+
+    let total = revisions.len();
+    let batch = revisions
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|r| revision_json(request, r))
+        .collect();
+    let next = (offset + limit < total).then(|| {
+        json!({
+            "rvcontinue": (offset + limit).to_string(),
+            "continue": "||",
+        })
+    });
+
+    (batch, next)
+}
+
+// Table rows of the lines that differ, a much simpler version of what the wiki renders.
+fn diff_rows(from: &str, to: &str) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let from: Vec<&str> = from.lines().collect();
+    let to: Vec<&str> = to.lines().collect();
+    (0..from.len().max(to.len()))
+        .filter(|&i| from.get(i) != to.get(i))
+        .map(|i| {
+            format!(
+                "<tr><td class=\"diff-deletedline\"><div>{}</div></td><td class=\"diff-addedline\"><div>{}</div></td></tr>",
+                escape(from.get(i).unwrap_or(&"")),
+                escape(to.get(i).unwrap_or(&"")),
+            )
+        })
+        .collect()
+}
+
+// Target of a `#REDIRECT [[Target]]` page.
+fn redirect(content: &str) -> Option<String> {
+    let rest = content.trim_start();
+    if !rest.get(..9)?.eq_ignore_ascii_case("#redirect") {
+        return None;
+    }
+    let (_, link) = rest.split_once("[[")?;
+    let (link, _) = link.split_once("]]")?;
+    Some(normalize(link.split('|').next().unwrap_or_default()))
+}
+
+// Targets of `[[links]]` starting with `prefix`, with the prefix stripped.
+fn links(content: &str, prefix: &str) -> Vec<String> {
+    content
+        .split("[[")
+        .skip(1)
+        .filter_map(|s| s.split_once("]]").map(|(link, _)| link))
+        .map(|link| link.split('|').next().unwrap_or_default())
+        .map(normalize)
+        .filter_map(|link| link.strip_prefix(prefix).map(ToString::to_string))
+        .collect()
+}
+
+// Titles of the templates transcluded via `{{Name}}`.
+fn templates(content: &str) -> Vec<String> {
+    content
+        .split("{{")
+        .skip(1)
+        .filter_map(|s| s.split_once("}}").map(|(t, _)| t))
+        .map(|t| {
+            format!(
+                "Template:{}",
+                normalize(t.split('|').next().unwrap_or_default())
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+use crate::{api::query::as_pairs, response::compare::Compare, Client, Error};
+
+pub use crate::response::compare::Response as Comparison;
+
+/// One side of a comparison via [`compare`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Revision(u64),
+    /// The current revision of a page.
+    Title(String),
+    /// Wikitext which isn't saved on the wiki, eg the result of Find&Replace.
+    Text(String),
+}
+
+impl Side {
+    fn parameters(&self, prefix: &str) -> Vec<(String, String)> {
+        match self {
+            Side::Revision(revid) => vec![(format!("{}rev", prefix), revid.to_string())],
+            Side::Title(title) => vec![(format!("{}title", prefix), title.clone())],
+            Side::Text(text) => vec![
+                (format!("{}slots", prefix), "main".to_string()),
+                (format!("{}text-main", prefix), text.clone()),
+                (
+                    format!("{}contentmodel-main", prefix),
+                    "wikitext".to_string(),
+                ),
+            ],
+        }
+    }
+}
+
+/// Let the wiki diff two revisions, pages or texts.
+///
+/// Fails with `nosuchrevid` or `missingtitle` if a side doesn't exist.
+pub async fn compare(client: &Client, from: &Side, to: &Side) -> Result<Comparison, Error> {
+    let mut parameters = vec![
+        ("action".to_string(), "compare".to_string()),
+        ("prop".to_string(), "diff|ids|title".to_string()),
+    ];
+    parameters.extend(from.parameters("from"));
+    parameters.extend(to.parameters("to"));
+
+    let res: Compare = client.get(&as_pairs(&parameters)).await?;
+    Ok(res.compare)
+}
+
+/// What [`diff`] compares, whole lines or single words.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    #[default]
+    Lines,
+    Words,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A run of text that is unchanged, only in the new text, or only in the old one.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub text: String,
+}
+
+/// Diff `old` and `new` locally, without a request.
+///
+/// Adjacent parts of the same kind are joined, so concatenating the `Equal` and `Delete` parts gives `old`,
+/// and the `Equal` and `Insert` parts give `new`.
+#[must_use]
+pub fn diff(old: &str, new: &str, granularity: Granularity) -> Vec<Change> {
+    let diff = match granularity {
+        Granularity::Lines => TextDiff::from_lines(old, new),
+        Granularity::Words => TextDiff::from_words(old, new),
+    };
+
+    let mut changes: Vec<Change> = Vec::new();
+    for change in diff.iter_all_changes() {
+        let kind = match change.tag() {
+            ChangeTag::Equal => ChangeKind::Equal,
+            ChangeTag::Insert => ChangeKind::Insert,
+            ChangeTag::Delete => ChangeKind::Delete,
+        };
+        match changes.last_mut() {
+            Some(last) if last.kind == kind => last.text.push_str(change.value()),
+            _ => changes.push(Change {
+                kind,
+                text: change.value().to_string(),
+            }),
+        }
+    }
+    changes
+}
@@ -1,33 +1,71 @@
+use crate::api::query::current_revisions;
+use crate::api::report::{ItemData, Outcome, Report};
 use crate::response::delete::Delete;
 use crate::Client;
 use crate::Error;
 
-pub async fn delete(client: &Client, titles: &[&str], reason: Option<&str>) -> Result<(), Error> {
-    for title in titles {
+/// Delete every page in `titles`.
+///
+/// Failing titles don't stop the deletion of the remaining ones, they are listed in the returned [`Report`].
+pub async fn delete(
+    client: &Client,
+    titles: &[&str],
+    reason: Option<&str>,
+) -> Result<Report, Error> {
+    client.require_rights(&["delete"]).await?;
+    let reason = reason.unwrap_or("automated action");
+    let mut report = Report::default();
+    let mut tracker = client.track("delete", Some(titles.len()));
+
+    if client.is_dry_run() {
+        let pages = current_revisions(client, titles, false).await?;
+        for title in titles {
+            match pages.get(*title) {
+                Some(page) if !page.missing => report.dry_run(
+                    client,
+                    &mut tracker,
+                    "delete",
+                    title,
+                    format!("would delete, reason: {}", reason),
+                ),
+                _ => report.dry_run(
+                    client,
+                    &mut tracker,
+                    "delete",
+                    title,
+                    "page doesn't exist, would fail",
+                ),
+            }
+        }
+        return Ok(report);
+    }
+
+    for (i, title) in titles.iter().enumerate() {
         let res: Result<Delete, Error> = client
-            .post(&[
-                ("action", "delete"),
-                ("reason", reason.unwrap_or("automated action")),
-                ("title", title),
-            ])
+            .post(&[("action", "delete"), ("reason", reason), ("title", title)])
             .await;
         match res {
-            Ok(_) => log::info!("successfully deleted \"{}\"", title),
+            Ok(res) => {
+                log::info!("successfully deleted \"{}\"", title);
+                report.push(
+                    &mut tracker,
+                    *title,
+                    Outcome::Success(ItemData {
+                        logid: res.delete.logid,
+                        ..ItemData::default()
+                    }),
+                );
+            }
+            Err(Error::Cancelled) => {
+                report.push_cancelled(titles[i..].iter().copied());
+                break;
+            }
             Err(err) => {
-                if let Error::MediaWikiApi(err) = err {
-                    log::error!(
-                        "deleting \"{}\" failed. reason: {} - {}",
-                        title,
-                        err.code,
-                        err.description
-                    );
-                } else {
-                    log::error!("deleting \"{}\" failed. reason: {}", title, err.to_string());
-                }
+                log::error!("deleting \"{}\" failed. reason: {}", title, err);
+                report.push(&mut tracker, *title, Outcome::Failed(err));
             }
         };
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     }
 
-    Ok(())
+    Ok(report)
 }
@@ -1,11 +1,13 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Mutex};
 
 use futures_util::{stream, StreamExt};
 use regex::Regex;
 use tokio::{fs::File, io::AsyncWriteExt};
 
 use crate::{
-    response::download::{Imageinfo, Page},
+    api::query::{Prop, Query},
+    client::Tracker,
+    response::query::Page,
     Client, Error,
 };
 
@@ -14,64 +16,65 @@ pub async fn download(client: &Client, files: &[&str]) -> Result<(), Error> {
         .and_then(|p| p.download_dir().map(|p| p.to_path_buf()))
         .expect("Can't find user's download folder!");
 
-    let len = files.len() - 1;
-    let mut titles = String::new();
-    let mut infos: Vec<crate::response::download::Page> = Vec::new();
+    let infos = Query::new()
+        .prop(Prop::ImageInfo)
+        .arg(Prop::ImageInfo, "prop", "url")
+        .titles(files)
+        .fetch(client)
+        .await?
+        .pages;
 
     let rgxp = Regex::new(r#"[<>:"/\|?*]+"#).unwrap();
 
-    for (i, f) in files.iter().enumerate() {
-        if !titles.is_empty() {
-            titles.push('|');
-        }
-        titles.push_str(f);
-
-        if titles.len() >= 1500 || i >= len {
-            let mut file_infos: Imageinfo = client
-                .get(&[
-                    ("action", "query"),
-                    ("prop", "imageinfo"),
-                    ("iiprop", "url"),
-                    ("titles", &titles),
-                ])
-                .await?;
-
-            infos.append(&mut file_infos.query.pages);
-
-            titles.clear();
-        }
-    }
-
+    let tracker = Mutex::new(client.track("download", Some(infos.len())));
     stream::iter(infos)
         .for_each_concurrent(8, |page| {
-            download_and_save(client.client().clone(), path.clone(), &rgxp, page)
+            download_and_save(client, path.clone(), &rgxp, page, &tracker)
         })
         .await;
 
-    Ok(())
+    client.check_cancelled()
 }
 
-async fn download_and_save(client: reqwest::Client, path: PathBuf, regex: &Regex, page: Page) {
-    match inner(client, path, regex, &page).await {
-        Ok(_) => log::info!(
-            "successfully saved \"{}\" to your downloads folder.",
-            page.title
-        ),
-        Err(err) => log::error!("couldn't download/save \"{}\". Error: {}", page.title, err),
+async fn download_and_save(
+    client: &Client,
+    path: PathBuf,
+    regex: &Regex,
+    page: Page,
+    tracker: &Mutex<Tracker<'_>>,
+) {
+    let res = inner(client, path, regex, &page).await;
+    let mut tracker = tracker.lock().unwrap_or_else(|e| e.into_inner());
+    match res {
+        Ok(_) => {
+            log::info!(
+                "successfully saved \"{}\" to your downloads folder.",
+                page.title
+            );
+            tracker.done(&page.title);
+        }
+        Err(err) => {
+            log::error!("couldn't download/save \"{}\". Error: {}", page.title, err);
+            tracker.failed(&page.title, &err);
+        }
     }
 }
 
 async fn inner(
-    client: reqwest::Client,
+    client: &Client,
     mut path: PathBuf,
     regex: &Regex,
     page: &Page,
 ) -> Result<(), Error> {
-    let imageinfo = page
+    let url = page
         .imageinfo
-        .as_ref()
+        .first()
+        .and_then(|info| info.url.as_deref())
         .ok_or_else(|| Error::InvalidInput("invalid wiki response".to_string()))?;
-    let file_contents = client.get(&imageinfo[0].url).send().await?.bytes().await?;
+    let file_contents = {
+        let _permit = client.unless_cancelled(client.read_permit()).await?;
+        client.client().get(url).send().await?.bytes().await?
+    };
 
     let file_name = page.title.splitn(2, ':').last().unwrap_or_default(/*This can't happen*/);
 
@@ -79,6 +82,8 @@ async fn inner(
 
     let mut file = File::create(&path).await?;
     file.write_all(&file_contents).await?;
+    // tokio finishes writes in the background unless flushed.
+    file.flush().await?;
 
     Ok(())
 }
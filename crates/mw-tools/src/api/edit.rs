@@ -1,38 +1,474 @@
-use crate::{response::edit::Edit, response::Ignore, Client, Error};
+use serde::Serialize;
 
-pub async fn nulledit(client: &Client, titles: &[&str]) -> Result<(), Error> {
-    for title in titles {
-        client
-            .post::<Ignore>(&[
-                ("action", "edit"),
-                ("summary", "Nulledit (broken if visible in RecentChanges)"),
-                ("notminor", "true"),
-                ("prependtext", ""),
-                ("nocreate", ""),
-                ("title", title),
-            ])
-            .await?;
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+use crate::{
+    api::{
+        parse::{get_page_content, PageContent},
+        query::current_revisions,
+        report::{ItemData, Outcome, Report},
+    },
+    response::edit::Edit,
+    Client, Error, ErrorCode,
+};
+
+pub use crate::response::edit::Response as EditResult;
+
+/// Returned as [`Error::EditConflict`] if the page changed since its content was fetched, with both versions for a merge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditConflict {
+    pub title: String,
+    /// The text that couldn't be saved.
+    pub ours: String,
+    /// The version the text was based on.
+    pub base: PageContent,
+    /// The current version, or `None` if the page got deleted in the meantime.
+    pub theirs: Option<PageContent>,
+}
+
+/// Save a null edit to every page in `titles`, eg to update links and categories.
+///
+/// Failing titles don't stop the remaining ones, they are listed in the returned [`Report`].
+pub async fn nulledit(client: &Client, titles: &[&str]) -> Result<Report, Error> {
+    client.require_rights(&["edit"]).await?;
+    let mut report = Report::default();
+    let mut tracker = client.track("nulledit", Some(titles.len()));
+
+    if client.is_dry_run() {
+        let pages = current_revisions(client, titles, false).await?;
+        for title in titles {
+            match pages.get(*title) {
+                Some(page) if !page.missing => report.dry_run(
+                    client,
+                    &mut tracker,
+                    "edit",
+                    title,
+                    "would save a null edit",
+                ),
+                _ => report.dry_run(
+                    client,
+                    &mut tracker,
+                    "edit",
+                    title,
+                    "page doesn't exist, the null edit would fail",
+                ),
+            }
+        }
+        return Ok(report);
     }
 
-    Ok(())
+    for (i, title) in titles.iter().enumerate() {
+        let res = EditRequest::new(*title)
+            .prepend("")
+            .summary("Nulledit (broken if visible in RecentChanges)")
+            .minor(false)
+            .create_mode(CreateMode::NoCreate)
+            .send(client)
+            .await;
+        match res {
+            Ok(res) => {
+                let revid = match res.newrevid {
+                    Some(revid) => Some(revid),
+                    None => current_revid(client, title).await,
+                };
+                report.push(
+                    &mut tracker,
+                    *title,
+                    Outcome::Success(ItemData {
+                        revid,
+                        ..ItemData::default()
+                    }),
+                );
+            }
+            Err(Error::Cancelled) => {
+                report.push_cancelled(titles[i..].iter().copied());
+                break;
+            }
+            Err(err) => {
+                log::error!("null edit of \"{}\" failed. reason: {}", title, err);
+                report.push(&mut tracker, *title, Outcome::Failed(err));
+            }
+        }
+    }
+
+    Ok(report)
 }
 
+// The API leaves out `newrevid` if nothing changed, so look up the revision the null edit left current.
+async fn current_revid(client: &Client, title: &str) -> Option<u64> {
+    match current_revisions(client, &[title], false).await {
+        Ok(pages) => pages
+            .get(title)
+            .and_then(|page| page.revisions.first())
+            .and_then(|revision| revision.revid),
+        Err(err) => {
+            log::warn!(
+                "couldn't get the current revision of \"{}\": {}",
+                title,
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Save `content` to `title`, replacing the whole page. Shorthand for [`EditRequest`].
+///
+/// Pass the [`PageContent`] the new content is based on as `base`, to fail with [`Error::EditConflict`]
+/// instead of overwriting edits made since it was fetched. Without it, the page gets overwritten unconditionally.
+///
+/// In dry-run mode, the change is compared to the current content and reported instead, and the result is `DryRun`.
 pub async fn edit(
     client: &Client,
     title: &str,
     content: &str,
     summary: Option<&str>,
-) -> Result<String, Error> {
-    let res: Edit = client
-        .post(&[
-            ("action", "edit"),
-            ("bot", ""),
-            ("summary", summary.unwrap_or("")),
-            ("title", title),
-            ("text", content),
-        ])
-        .await?;
-
-    Ok(res.edit.result)
+    base: Option<&PageContent>,
+) -> Result<EditResult, Error> {
+    let mut request = EditRequest::new(title).text(content);
+    if let Some(summary) = summary {
+        request = request.summary(summary);
+    }
+    if let Some(base) = base {
+        request = request.base(base.clone());
+    }
+    request.send(client).await
+}
+
+/// Whether an edit may create the page.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CreateMode {
+    /// Create the page if it doesn't exist.
+    #[default]
+    Any,
+    /// Fail with `articleexists` if the page exists.
+    CreateOnly,
+    /// Fail with `missingtitle` if the page doesn't exist.
+    NoCreate,
+}
+
+/// What to do with the page on the watchlist of the account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Watchlist {
+    Watch,
+    Unwatch,
+    /// Use the preference of the account, the default of the API.
+    Preferences,
+    NoChange,
+}
+
+impl Watchlist {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Watchlist::Watch => "watch",
+            Watchlist::Unwatch => "unwatch",
+            Watchlist::Preferences => "preferences",
+            Watchlist::NoChange => "nochange",
+        }
+    }
+}
+
+/// Builder for `action=edit` requests.
+///
+/// Set the new content via [`text`](Self::text), or add to the current one via [`prepend`](Self::prepend)
+/// and [`append`](Self::append). Edits are flagged as bot edits if the account has the `bot` right, unless
+/// [`bot`](Self::bot) says otherwise.
+/// # Example
+/// ```no_run
+/// # async fn test_edit() -> Result<(), mw_tools::Error> {
+/// # let client = mw_tools::Client::new("")?;
+/// use mw_tools::api::edit::{CreateMode, EditRequest};
+///
+/// // Add a section to an existing talk page.
+/// let result = EditRequest::new("Talk:Main Page")
+///     .new_section("Broken link")
+///     .text("The link to [[Foo]] is broken. ~~~~")
+///     .create_mode(CreateMode::NoCreate)
+///     .tags(&["bot-report"])
+///     .send(&client)
+///     .await?;
+///
+/// println!("saved revision {:?}", result.newrevid);
+/// # Ok(())}
+/// ```
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct EditRequest {
+    title: String,
+    text: Option<String>,
+    prependtext: Option<String>,
+    appendtext: Option<String>,
+    section: Option<String>,
+    sectiontitle: Option<String>,
+    summary: Option<String>,
+    minor: Option<bool>,
+    bot: Option<bool>,
+    create: CreateMode,
+    watchlist: Option<Watchlist>,
+    tags: Vec<String>,
+    recreate: bool,
+    md5: bool,
+    base: Option<PageContent>,
+}
+
+impl EditRequest {
+    pub fn new<S: Into<String>>(title: S) -> Self {
+        Self {
+            title: title.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Replace the content of the page, or of the [`section`](Self::section).
+    pub fn text<S: Into<String>>(mut self, text: S) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Add text to the start of the page, or of the [`section`](Self::section).
+    pub fn prepend<S: Into<String>>(mut self, text: S) -> Self {
+        self.prependtext = Some(text.into());
+        self
+    }
+
+    /// Add text to the end of the page, or of the [`section`](Self::section).
+    pub fn append<S: Into<String>>(mut self, text: S) -> Self {
+        self.appendtext = Some(text.into());
+        self
+    }
+
+    /// Only edit a section, by its number. `0` is the part before the first heading.
+    pub fn section<S: Into<String>>(mut self, section: S) -> Self {
+        self.section = Some(section.into());
+        self
+    }
+
+    /// Add a new section with this heading. The [`text`](Self::text) becomes its content.
+    pub fn new_section<S: Into<String>>(mut self, heading: S) -> Self {
+        self.section = Some("new".to_string());
+        self.sectiontitle = Some(heading.into());
+        self
+    }
+
+    pub fn summary<S: Into<String>>(mut self, summary: S) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Mark the edit as minor, or explicitly not, overriding the preference of the account.
+    pub fn minor(mut self, minor: bool) -> Self {
+        self.minor = Some(minor);
+        self
+    }
+
+    /// Flag the edit as a bot edit. Requires the `bot` right.
+    pub fn bot(mut self, bot: bool) -> Self {
+        self.bot = Some(bot);
+        self
+    }
+
+    pub fn create_mode(mut self, mode: CreateMode) -> Self {
+        self.create = mode;
+        self
+    }
+
+    pub fn watchlist(mut self, watchlist: Watchlist) -> Self {
+        self.watchlist = Some(watchlist);
+        self
+    }
+
+    /// Change tags to apply to the edit. They need to be defined and active on the wiki.
+    pub fn tags<S: AsRef<str>>(mut self, tags: &[S]) -> Self {
+        self.tags = tags.iter().map(|t| t.as_ref().to_string()).collect();
+        self
+    }
+
+    /// Save the page even if it got deleted since the [`base`](Self::base) was fetched.
+    pub fn recreate(mut self, recreate: bool) -> Self {
+        self.recreate = recreate;
+        self
+    }
+
+    /// Send the MD5 hash of the content, so the API rejects the edit with `badmd5` if it got corrupted in transit.
+    pub fn verify_md5(mut self, verify: bool) -> Self {
+        self.md5 = verify;
+        self
+    }
+
+    /// The version the edit is based on. Makes the edit fail with [`Error::EditConflict`]
+    /// instead of overwriting edits made since it was fetched.
+    pub fn base(mut self, base: PageContent) -> Self {
+        self.base = Some(base);
+        self
+    }
+
+    // The content sent in any of the three parameters. `md5` is calculated over the same.
+    fn content(&self) -> String {
+        match &self.text {
+            Some(text) => text.clone(),
+            None => format!(
+                "{}{}",
+                self.prependtext.as_deref().unwrap_or_default(),
+                self.appendtext.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+
+    fn build(&self, bot: bool) -> Result<Vec<(&'static str, String)>, Error> {
+        let adds = self.prependtext.is_some() || self.appendtext.is_some();
+        match (&self.text, adds) {
+            (None, false) => {
+                return Err(Error::InvalidInput(
+                    "one of text, prependtext and appendtext is required".to_string(),
+                ))
+            }
+            (Some(_), true) => {
+                return Err(Error::InvalidInput(
+                    "text can't be combined with prependtext or appendtext".to_string(),
+                ))
+            }
+            _ => {}
+        }
+
+        let mut parameters = vec![
+            ("action", "edit".to_string()),
+            ("title", self.title.clone()),
+        ];
+        let optional = [
+            ("text", &self.text),
+            ("prependtext", &self.prependtext),
+            ("appendtext", &self.appendtext),
+            ("section", &self.section),
+            ("sectiontitle", &self.sectiontitle),
+            ("summary", &self.summary),
+        ];
+        for (name, value) in optional {
+            if let Some(value) = value {
+                parameters.push((name, value.clone()));
+            }
+        }
+
+        let flags = [
+            ("minor", self.minor == Some(true)),
+            ("notminor", self.minor == Some(false)),
+            ("bot", bot),
+            ("createonly", self.create == CreateMode::CreateOnly),
+            ("nocreate", self.create == CreateMode::NoCreate),
+            ("recreate", self.recreate),
+        ];
+        for (name, set) in flags {
+            if set {
+                parameters.push((name, String::new()));
+            }
+        }
+
+        if let Some(watchlist) = self.watchlist {
+            parameters.push(("watchlist", watchlist.as_str().to_string()));
+        }
+        if !self.tags.is_empty() {
+            parameters.push(("tags", self.tags.join("|")));
+        }
+        if self.md5 {
+            parameters.push(("md5", format!("{:x}", md5::compute(self.content()))));
+        }
+        if let Some(base) = &self.base {
+            parameters.extend([
+                ("baserevid", base.revid.to_string()),
+                ("basetimestamp", base.timestamp.clone()),
+                ("starttimestamp", base.starttimestamp.clone()),
+            ]);
+        }
+
+        Ok(parameters)
+    }
+
+    /// Save the edit.
+    ///
+    /// In dry-run mode, the change is compared to the current content and reported instead, and the result is `DryRun`.
+    pub async fn send(&self, client: &Client) -> Result<EditResult, Error> {
+        client.require_rights(&["edit"]).await?;
+        // Only accounts with the bot right may flag their edits.
+        let bot = match self.bot {
+            Some(bot) => bot,
+            None => client.user_info().await?.has_right("bot"),
+        };
+        let parameters = self.build(bot)?;
+
+        if client.is_dry_run() {
+            let description = self.describe(client).await?;
+            client.report_change("edit", &self.title, description);
+            return Ok(EditResult {
+                result: "DryRun".to_string(),
+                title: self.title.clone(),
+                ..EditResult::default()
+            });
+        }
+
+        let parameters: Vec<(&str, &str)> =
+            parameters.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        match client.post::<Edit>(&parameters).await {
+            Ok(res) => Ok(res.edit),
+            Err(Error::MediaWikiApi(err))
+                if matches!(err.code, ErrorCode::EditConflict | ErrorCode::PageDeleted) =>
+            {
+                let Some(base) = &self.base else {
+                    return Err(Error::MediaWikiApi(err));
+                };
+                let theirs = match get_page_content(client, &self.title).await {
+                    Ok(page) => Some(page),
+                    Err(Error::MediaWikiApi(err)) if err.code == ErrorCode::MissingTitle => None,
+                    Err(err) => return Err(err),
+                };
+                Err(Error::EditConflict(Box::new(EditConflict {
+                    title: self.title.clone(),
+                    ours: self.content(),
+                    base: base.clone(),
+                    theirs,
+                })))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    // What the edit would do, for dry-run mode.
+    async fn describe(&self, client: &Client) -> Result<String, Error> {
+        let pages = current_revisions(client, &[self.title.as_str()], true).await?;
+        let Some(page) = pages.get(&self.title) else {
+            return Ok("invalid title, the edit would fail".to_string());
+        };
+        let content = self.content();
+
+        let current = page.revisions.first().filter(|_| !page.missing);
+        let description = match (current, self.create) {
+            (None, CreateMode::NoCreate) => "page doesn't exist, the edit would fail".to_string(),
+            (None, _) => format!("would create the page with {} bytes", content.len()),
+            (Some(_), CreateMode::CreateOnly) => {
+                "page already exists, the edit would fail".to_string()
+            }
+            (Some(_), _) if self.text.is_none() => format!("would add {} bytes", content.len()),
+            (Some(_), _) if self.sectiontitle.is_some() => format!(
+                "would add the section \"{}\" with {} bytes",
+                self.sectiontitle.as_deref().unwrap_or_default(),
+                content.len()
+            ),
+            (Some(_), _) if self.section.is_some() => format!(
+                "would replace section {} with {} bytes",
+                self.section.as_deref().unwrap_or_default(),
+                content.len()
+            ),
+            (Some(current), _) => {
+                let current = current.content().unwrap_or_default();
+                if current == content {
+                    "no change".to_string()
+                } else {
+                    format!(
+                        "would change the page from {} to {} bytes",
+                        current.len(),
+                        content.len()
+                    )
+                }
+            }
+        };
+        Ok(description)
+    }
 }
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+
+use crate::{
+    api::{
+        parse::{api_error, page_error, PageContent},
+        query::{Prop, Query},
+    },
+    response::query::Revision,
+    Client, Error, ErrorCode,
+};
+
+type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Builder listing the revisions of a page, newest first.
+///
+/// The revisions come without content, use [`get_revision`] for that.
+/// Fails with `missingtitle` if the page doesn't exist, and with `invalidtitle` if the title isn't valid.
+/// # Example
+/// ```no_run
+/// # async fn test_history() -> Result<(), mw_tools::Error> {
+/// # let client = mw_tools::Client::new("")?;
+/// use mw_tools::api::{edit::EditRequest, history::{get_revision, History}};
+///
+/// // Restore the page to the last revision not made by Vandal.
+/// let revisions = History::new("Page").exclude_user("Vandal").limit(1).fetch(&client).await?;
+/// if let Some(revid) = revisions.first().and_then(|r| r.revid) {
+///     let old = get_revision(&client, revid).await?;
+///     EditRequest::new("Page")
+///         .text(old.wikitext)
+///         .summary(format!("Restore revision {}", revid))
+///         .send(&client)
+///         .await?;
+/// }
+/// # Ok(())}
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct History {
+    title: String,
+    limit: Option<usize>,
+    user: Option<String>,
+    exclude_user: Option<String>,
+    oldest_first: bool,
+}
+
+impl History {
+    pub fn new<S: Into<String>>(title: S) -> Self {
+        Self {
+            title: title.into(),
+            limit: None,
+            user: None,
+            exclude_user: None,
+            oldest_first: false,
+        }
+    }
+
+    /// Stop after `limit` revisions. All revisions are listed by default.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only list revisions made by `user`.
+    pub fn user<S: Into<String>>(mut self, user: S) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Leave out revisions made by `user`.
+    pub fn exclude_user<S: Into<String>>(mut self, user: S) -> Self {
+        self.exclude_user = Some(user.into());
+        self
+    }
+
+    /// List the oldest revision first.
+    pub fn oldest_first(mut self) -> Self {
+        self.oldest_first = true;
+        self
+    }
+
+    fn query(&self) -> Query {
+        let limit = self
+            .limit
+            .map_or_else(|| "max".to_string(), |l| l.to_string());
+        let mut query = Query::new()
+            .prop(Prop::Revisions)
+            .arg(
+                Prop::Revisions,
+                "prop",
+                "ids|timestamp|user|size|comment|tags",
+            )
+            .arg(Prop::Revisions, "limit", limit)
+            .titles(&[&self.title]);
+        if let Some(user) = &self.user {
+            query = query.arg(Prop::Revisions, "user", user);
+        }
+        if let Some(user) = &self.exclude_user {
+            query = query.arg(Prop::Revisions, "excludeuser", user);
+        }
+        if self.oldest_first {
+            query = query.arg(Prop::Revisions, "dir", "newer");
+        }
+        query
+    }
+
+    /// Stream the revisions, fetching further batches as needed.
+    pub fn stream<'a>(&self, client: &'a Client) -> impl Stream<Item = Result<Revision>> + 'a {
+        self.query()
+            .stream(client)
+            .map_ok(|result| {
+                let revisions: Vec<Result<Revision>> = result
+                    .pages
+                    .into_iter()
+                    .flat_map(|page| match page_error(&page) {
+                        Some(err) => vec![Err(err)],
+                        None => page.revisions.into_iter().map(Ok).collect(),
+                    })
+                    .collect();
+                stream::iter(revisions)
+            })
+            .try_flatten()
+            .take(self.limit.unwrap_or(usize::MAX))
+    }
+
+    /// Fetch all revisions.
+    pub async fn fetch(&self, client: &Client) -> Result<Vec<Revision>> {
+        self.stream(client).try_collect().await
+    }
+}
+
+/// Stream the revisions of many pages, one page after another, together with the title as given.
+pub fn histories<'a, I>(
+    client: &'a Client,
+    histories: I,
+) -> impl Stream<Item = Result<(String, Revision)>> + 'a
+where
+    I: IntoIterator<Item = History>,
+    I::IntoIter: 'a,
+{
+    stream::iter(histories).flat_map(move |history| {
+        let title = history.title.clone();
+        history
+            .stream(client)
+            .map_ok(move |revision| (title.clone(), revision))
+    })
+}
+
+/// Get the wikitext of an old or current revision.
+///
+/// Fails with `nosuchrevid` if the revision doesn't exist, and with `permissiondenied` if its content is hidden.
+pub async fn get_revision(client: &Client, revid: u64) -> Result<PageContent> {
+    revisions_content(client, &[revid])
+        .await?
+        .into_iter()
+        .find(|(id, _)| *id == revid)
+        .map_or_else(
+            || {
+                Err(api_error(
+                    ErrorCode::NoSuchRevId,
+                    &format!("There is no revision with ID {}.", revid),
+                ))
+            },
+            |(_, content)| content,
+        )
+}
+
+/// Get the wikitext of many revisions, keyed by revision id.
+/// Revisions that don't exist or whose content is hidden are left out.
+pub async fn get_revisions(client: &Client, revids: &[u64]) -> Result<HashMap<u64, PageContent>> {
+    Ok(revisions_content(client, revids)
+        .await?
+        .into_iter()
+        .filter_map(|(revid, content)| Some((revid, content.ok()?)))
+        .collect())
+}
+
+async fn revisions_content(
+    client: &Client,
+    revids: &[u64],
+) -> Result<Vec<(u64, Result<PageContent>)>> {
+    let result = Query::new()
+        .prop(Prop::Revisions)
+        .arg(Prop::Revisions, "prop", "ids|timestamp|content")
+        .arg(Prop::Revisions, "slots", "main")
+        .param("curtimestamp", "1")
+        .revids(revids)
+        .fetch(client)
+        .await?;
+    let starttimestamp = result.curtimestamp.unwrap_or_default();
+
+    Ok(result
+        .pages
+        .iter()
+        .flat_map(|page| page.revisions.iter().map(move |r| (page, r)))
+        .filter_map(|(page, revision)| {
+            let content = PageContent::new(page, revision, &starttimestamp);
+            revision.revid.map(|revid| (revid, content))
+        })
+        .collect())
+}
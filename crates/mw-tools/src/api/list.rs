@@ -1,233 +1,136 @@
 use std::collections::HashMap;
 
+use futures_util::TryStreamExt;
+
 use crate::{
-    response::list::{List, Namespaces, Querypage},
+    api::query::{List, Query},
+    response::query::ListItem,
     Client, Error,
 };
 
 type Result<T, E = Error> = core::result::Result<T, E>;
 
 pub async fn allcategories(client: &Client) -> Result<Vec<String>> {
-    get_from_api(client, "allcategories", "ac", None).await
+    get_from_api(client, List::AllCategories, None).await
 }
 
 pub async fn allimages(client: &Client) -> Result<Vec<String>> {
-    get_from_api(client, "allimages", "ai", None).await
+    get_from_api(client, List::AllImages, None).await
 }
 
 pub async fn allinfoboxes(client: &Client) -> Result<Vec<String>> {
-    get_from_api(client, "allinfoboxes", "", None).await
+    get_from_api(
+        client,
+        List::Other {
+            name: "allinfoboxes".to_string(),
+            prefix: String::new(),
+        },
+        None,
+    )
+    .await
 }
 
 pub async fn alllinks(client: &Client) -> Result<Vec<String>> {
-    get_from_api(client, "alllinks", "al", None).await
+    get_from_api(client, List::AllLinks, None).await
 }
 
 pub async fn allpages(client: &Client, parameter: Option<&str>) -> Result<Vec<String>> {
     if let Some(param) = parameter {
         if param == "all" {
             let mut temp: Vec<String> = Vec::new();
-            let ns_res: Namespaces = client
-                .get(&[
-                    ("action", "query"),
-                    ("meta", "siteinfo"),
-                    ("siprop", "namespaces"),
-                ])
-                .await?;
-            for ns in ns_res.query.namespaces.keys() {
-                let num = ns.parse::<i32>();
-                if let Ok(i) = num {
-                    if i < 0 {
-                        continue;
-                    }
-                }
+            let site_info = client.site_info().await?;
+            for ns in site_info.namespaces.iter().filter(|ns| ns.id >= 0) {
                 temp.append(
                     &mut get_from_api(
                         client,
-                        "allpages",
-                        "ap",
-                        Some(&format!("apnamespace={}", ns)),
+                        List::AllPages,
+                        Some(("namespace", &ns.id.to_string())),
                     )
                     .await?,
                 );
             }
             return Ok(temp);
         }
-        return get_from_api(
-            client,
-            "allpages",
-            "ap",
-            Some(&format!("apnamespace={}", param)),
-        )
-        .await;
+        return get_from_api(client, List::AllPages, Some(("namespace", param))).await;
     }
-    get_from_api(client, "allpages", "ap", None).await
+    get_from_api(client, List::AllPages, None).await
 }
 
 pub async fn backlinks(client: &Client, parameter: &str) -> Result<Vec<String>> {
-    get_from_api(
-        client,
-        "backlinks",
-        "bl",
-        Some(&format!("bltitle={}", parameter)),
-    )
-    .await
+    get_from_api(client, List::Backlinks, Some(("title", parameter))).await
 }
 
 pub async fn categorymembers(client: &Client, parameter: &str) -> Result<Vec<String>> {
-    get_from_api(
-        client,
-        "categorymembers",
-        "cm",
-        Some(&format!("cmtitle={}", parameter)),
-    )
-    .await
+    get_from_api(client, List::CategoryMembers, Some(("title", parameter))).await
 }
 
 pub async fn embeddedin(client: &Client, parameter: &str) -> Result<Vec<String>> {
-    get_from_api(
-        client,
-        "embeddedin",
-        "ei",
-        Some(&format!("eititle={}", parameter)),
-    )
-    .await
+    get_from_api(client, List::EmbeddedIn, Some(("title", parameter))).await
 }
 
 pub async fn exturlusage(client: &Client) -> Result<HashMap<String, Vec<String>>> {
-    let api_res = get_from_api(client, "exturlusage", "eu", None).await?;
+    let api_res = get_items(client, List::ExtUrlUsage, None).await?;
     let mut results: HashMap<String, Vec<String>> = HashMap::new();
 
-    for v in api_res {
-        let split: Vec<&str> = v.split("~URL~").collect();
-        results
-            .entry(split[0].to_string())
-            .or_default()
-            .push(split[1].to_string());
+    for item in api_res {
+        let url = item
+            .extra
+            .get("url")
+            .and_then(|u| u.as_str())
+            .unwrap_or_default()
+            .to_string();
+        results.entry(item.title).or_default().push(url);
     }
 
     Ok(results)
 }
 
 pub async fn imageusage(client: &Client, parameter: &str) -> Result<Vec<String>> {
-    get_from_api(
-        client,
-        "imageusage",
-        "iu",
-        Some(&format!("iutitle={}", parameter)),
-    )
-    .await
+    get_from_api(client, List::ImageUsage, Some(("title", parameter))).await
 }
 
 pub async fn protectedtitles(client: &Client) -> Result<Vec<String>> {
-    get_from_api(client, "protectedtitles", "pt", None).await
+    get_from_api(client, List::ProtectedTitles, None).await
 }
 
 pub async fn querypage(client: &Client, parameter: &str) -> Result<Vec<String>> {
-    get_from_api(
-        client,
-        "querypage",
-        "qp",
-        Some(&format!("qppage={}", parameter)),
-    )
-    .await
+    get_from_api(client, List::QueryPage, Some(("page", parameter))).await
 }
 
 pub async fn search(client: &Client, parameter: &str) -> Result<Vec<String>> {
-    get_from_api(
-        client,
-        "search",
-        "sr",
-        Some(&format!("srsearch={}", parameter)),
-    )
-    .await
+    get_from_api(client, List::Search, Some(("search", parameter))).await
 }
 
 async fn get_from_api(
     api: &Client,
-    long: &str,
-    short: &str,
-    parameter: Option<&str>,
+    list: List,
+    parameter: Option<(&str, &str)>,
 ) -> Result<Vec<String>> {
-    let mut has_next: bool = true;
-    let mut continue_from = String::new();
-    let mut results: Vec<String> = Vec::new();
-    let cont = match short {
-        "qp" | "sr" => "offset",
-        _ => "continue",
-    };
-    let param = match parameter {
-        Some(p) => {
-            let temp: Vec<&str> = p.split('=').collect();
-            (temp[0], temp[1])
-        }
-        None => ("", ""),
-    };
-
-    match short {
-        "qp" => {
-            while has_next {
-                let json: Querypage = api
-                    .get(&[
-                        ("action", "query"),
-                        ("list", long),
-                        ("qplimit", "500"),
-                        ("qpoffset", &continue_from),
-                        param,
-                    ])
-                    .await?;
-
-                for page in json.query.querypage.results {
-                    results.push(page.title);
-                }
-
-                if let Some(c) = json.querycontinue {
-                    continue_from = c.from;
-                } else {
-                    has_next = false;
-                };
-            }
-        }
-        _ => {
-            while has_next {
-                let json: List = api
-                    .get(&[
-                        ("action", "query"),
-                        ("list", long),
-                        (&format!("{}limit", short), "5000"),
-                        (
-                            &format!(
-                                "{}{}",
-                                if continue_from.is_empty() { "" } else { short },
-                                cont
-                            ),
-                            &continue_from,
-                        ),
-                        param,
-                    ])
-                    .await?;
-
-                for page in json.query.pages {
-                    match short {
-                        "eu" => {
-                            results.push(format!(
-                                "{}~URL~{}",
-                                page.title,
-                                page.url.unwrap_or_default()
-                            ));
-                        }
-                        _ => results.push(page.title),
-                    }
-                }
-
-                if let Some(c) = json.querycontinue {
-                    continue_from = c.from;
-                } else {
-                    has_next = false;
-                };
-            }
-        }
+    Ok(get_items(api, list, parameter)
+        .await?
+        .into_iter()
+        .map(|item| item.title)
+        .collect())
+}
+
+async fn get_items(
+    api: &Client,
+    list: List,
+    parameter: Option<(&str, &str)>,
+) -> Result<Vec<ListItem>> {
+    let mut query = Query::new().list(list.clone()).limit("max");
+    if let Some((name, value)) = parameter {
+        query = query.arg(list.clone(), name, value);
     }
 
-    Ok(results)
+    let mut tracker = api.track("list", None);
+    let mut items = Vec::new();
+    let mut batches = std::pin::pin!(query.stream(api));
+    while let Some(mut batch) = batches.try_next().await? {
+        let batch = batch.lists.remove(list.name()).unwrap_or_default();
+        batch.iter().for_each(|item| tracker.done(&item.title));
+        items.extend(batch);
+    }
+
+    Ok(items)
 }
@@ -1,8 +1,12 @@
+pub mod compare;
 pub mod delete;
 pub mod download;
 pub mod edit;
+pub mod history;
 pub mod list;
 pub mod parse;
 pub mod purge;
+pub mod query;
 pub mod rename;
+pub mod report;
 pub mod upload;
@@ -1,9 +1,165 @@
-use crate::{response::parse::Parse, Client, Error};
+use serde::{Deserialize, Serialize};
 
-pub async fn get_page_content(client: &Client, page: &str) -> Result<String, Error> {
-    let res: Parse = client
-        .get(&[("action", "parse"), ("prop", "wikitext"), ("page", page)])
-        .await?;
+use crate::{
+    api::query::{Prop, Query},
+    response::{
+        self,
+        query::{Page, Revision},
+        ErrorCode,
+    },
+    Client, Error,
+};
 
-    Ok(res.parse.wikitext)
+/// Wikitext of a page, together with what [`edit`](crate::api::edit::edit) needs to detect edit conflicts.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageContent {
+    /// The normalized title.
+    pub title: String,
+    pub wikitext: String,
+    /// Id of the revision the wikitext belongs to.
+    pub revid: u64,
+    /// Timestamp of that revision, sent back as `basetimestamp`.
+    pub timestamp: String,
+    /// When the content was fetched, sent back as `starttimestamp`.
+    pub starttimestamp: String,
+}
+
+impl PageContent {
+    // Fails with `permissiondenied` if the content of the revision is hidden, so it can't be mistaken for an empty page.
+    pub(crate) fn new(
+        page: &Page,
+        revision: &Revision,
+        starttimestamp: &str,
+    ) -> Result<Self, Error> {
+        let wikitext = revision.content().ok_or_else(|| {
+            api_error(
+                ErrorCode::PermissionDenied,
+                "You don't have permission to view deleted text or changes between deleted revisions.",
+            )
+        })?;
+        Ok(Self {
+            title: page.title.clone(),
+            wikitext: wikitext.to_string(),
+            revid: revision.revid.unwrap_or_default(),
+            timestamp: revision.timestamp.clone().unwrap_or_default(),
+            starttimestamp: starttimestamp.to_string(),
+        })
+    }
+}
+
+/// Content of one of the titles passed to [`get_pages_content`].
+#[derive(Debug)]
+pub struct FetchedPage {
+    /// The title as passed in.
+    pub requested: String,
+    /// The title the page redirected to, if redirects were followed.
+    pub redirect: Option<String>,
+    /// The content, or `missingtitle`/`invalidtitle` if there is none and `permissiondenied` if it's hidden.
+    pub content: Result<PageContent, Error>,
+}
+
+/// Get the wikitext of the current revision of `page`.
+///
+/// Fails with `missingtitle` if the page doesn't exist, with `invalidtitle` if the title isn't valid,
+/// and with `permissiondenied` if the content of the revision is hidden.
+pub async fn get_page_content(client: &Client, page: &str) -> Result<PageContent, Error> {
+    get_pages_content(client, &[page], false)
+        .await?
+        .pop()
+        .ok_or_else(|| Error::ParsingFailed("response contains no page".to_string()))?
+        .content
+}
+
+/// Get the wikitext of the current revisions of `titles`, in as few requests as the wiki's limits allow.
+///
+/// Returns one entry per title, in the given order. Missing and invalid titles don't fail the whole call,
+/// they are reported in [`FetchedPage::content`]. With `redirects` the content of redirect targets is returned.
+pub async fn get_pages_content<S: AsRef<str>>(
+    client: &Client,
+    titles: &[S],
+    redirects: bool,
+) -> Result<Vec<FetchedPage>, Error> {
+    let mut query = Query::new()
+        .prop(Prop::Revisions)
+        .arg(Prop::Revisions, "prop", "ids|timestamp|content")
+        .arg(Prop::Revisions, "slots", "main")
+        .param("curtimestamp", "1")
+        .titles(titles);
+    if redirects {
+        query = query.redirects();
+    }
+    let result = query.fetch(client).await?;
+    let starttimestamp = result.curtimestamp.clone().unwrap_or_default();
+
+    let fetched = titles
+        .iter()
+        .map(|title| {
+            let title = title.as_ref();
+            let normalized = result
+                .normalized
+                .iter()
+                .find(|m| m.from == title)
+                .map_or(title, |m| m.to.as_str());
+            let redirect = result
+                .redirects
+                .iter()
+                .find(|m| m.from == normalized)
+                .map(|m| m.to.clone());
+            let target = redirect.as_deref().unwrap_or(normalized);
+
+            let content = match result.pages.iter().find(|p| p.title == target) {
+                Some(page) => match page_error(page) {
+                    Some(err) => Err(err),
+                    None => page
+                        .revisions
+                        .first()
+                        .ok_or_else(missing)
+                        .and_then(|revision| PageContent::new(page, revision, &starttimestamp)),
+                },
+                None => Err(missing()),
+            };
+
+            FetchedPage {
+                requested: title.to_string(),
+                redirect,
+                content,
+            }
+        })
+        .collect();
+
+    Ok(fetched)
+}
+
+/// The `invalidtitle` or `missingtitle` error for a page the query couldn't find.
+pub(crate) fn page_error(page: &Page) -> Option<Error> {
+    if page.invalid {
+        let reason = page.extra.get("invalidreason").and_then(|r| r.as_str());
+        Some(api_error(
+            ErrorCode::InvalidTitle,
+            reason.unwrap_or("Bad title."),
+        ))
+    } else if page.missing {
+        Some(missing())
+    } else {
+        None
+    }
+}
+
+fn missing() -> Error {
+    api_error(
+        ErrorCode::MissingTitle,
+        "The page you specified doesn't exist.",
+    )
+}
+
+// Same errors as action=parse returns for missing and invalid pages.
+pub(crate) fn api_error(code: ErrorCode, description: &str) -> Error {
+    Error::MediaWikiApi(response::Error {
+        code,
+        description: description.to_string(),
+        module: "main".to_string(),
+        data: None,
+        additional: Vec::new(),
+    })
 }
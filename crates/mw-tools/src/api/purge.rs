@@ -1,16 +1,44 @@
-use crate::{response::Ignore, Client, Error};
+use crate::{api::query::current_revisions, response::Ignore, Client, Error};
 
 pub async fn purge(client: &Client, titles: &[&str], recursive: bool) -> Result<(), Error> {
-    for chunk in titles.chunks(50) {
-        client
+    let mut tracker = client.track("purge", Some(titles.len()));
+
+    if client.is_dry_run() {
+        let pages = current_revisions(client, titles, false).await?;
+        for title in titles {
+            tracker.done(title);
+            match pages.get(*title) {
+                Some(page) if !page.missing => client.report_change(
+                    "purge",
+                    title,
+                    if recursive {
+                        "would purge and update links recursively"
+                    } else {
+                        "would purge and update links"
+                    },
+                ),
+                _ => client.report_change("purge", title, "page doesn't exist, would be skipped"),
+            }
+        }
+        return Ok(());
+    }
+
+    for chunk in client.batches(titles).await? {
+        let res = client
             .post::<Ignore>(&[
                 ("action", "purge"),
                 ("forcelinkupdate", "true"),
                 ("forcerecursivelinkupdate", &recursive.to_string()),
                 ("titles", &chunk.join("|")),
             ])
-            .await?;
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            .await;
+        match res {
+            Ok(_) => chunk.iter().for_each(|title| tracker.done(title)),
+            Err(err) => {
+                chunk.iter().for_each(|title| tracker.failed(title, &err));
+                return Err(err);
+            }
+        }
     }
 
     Ok(())
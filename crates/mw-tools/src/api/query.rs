@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+
+use crate::{
+    response::query::{Batch, Page, QueryResult},
+    Client, Error,
+};
+
+/// Modules usable with `list=`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum List {
+    AllCategories,
+    AllImages,
+    AllLinks,
+    AllPages,
+    Backlinks,
+    CategoryMembers,
+    EmbeddedIn,
+    ExtUrlUsage,
+    ImageUsage,
+    ProtectedTitles,
+    QueryPage,
+    Search,
+    /// Any other list module, with its parameter prefix.
+    Other {
+        name: String,
+        prefix: String,
+    },
+}
+
+impl List {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            List::AllCategories => "allcategories",
+            List::AllImages => "allimages",
+            List::AllLinks => "alllinks",
+            List::AllPages => "allpages",
+            List::Backlinks => "backlinks",
+            List::CategoryMembers => "categorymembers",
+            List::EmbeddedIn => "embeddedin",
+            List::ExtUrlUsage => "exturlusage",
+            List::ImageUsage => "imageusage",
+            List::ProtectedTitles => "protectedtitles",
+            List::QueryPage => "querypage",
+            List::Search => "search",
+            List::Other { name, .. } => name,
+        }
+    }
+
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        match self {
+            List::AllCategories => "ac",
+            List::AllImages => "ai",
+            List::AllLinks => "al",
+            List::AllPages => "ap",
+            List::Backlinks => "bl",
+            List::CategoryMembers => "cm",
+            List::EmbeddedIn => "ei",
+            List::ExtUrlUsage => "eu",
+            List::ImageUsage => "iu",
+            List::ProtectedTitles => "pt",
+            List::QueryPage => "qp",
+            List::Search => "sr",
+            List::Other { prefix, .. } => prefix,
+        }
+    }
+}
+
+/// Modules usable with `prop=`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Prop {
+    Categories,
+    ImageInfo,
+    Info,
+    Links,
+    Revisions,
+    Templates,
+    /// Any other prop module, with its parameter prefix.
+    Other {
+        name: String,
+        prefix: String,
+    },
+}
+
+impl Prop {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Prop::Categories => "categories",
+            Prop::ImageInfo => "imageinfo",
+            Prop::Info => "info",
+            Prop::Links => "links",
+            Prop::Revisions => "revisions",
+            Prop::Templates => "templates",
+            Prop::Other { name, .. } => name,
+        }
+    }
+
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        match self {
+            Prop::Categories => "cl",
+            Prop::ImageInfo => "ii",
+            Prop::Info => "in",
+            Prop::Links => "pl",
+            Prop::Revisions => "rv",
+            Prop::Templates => "tl",
+            Prop::Other { prefix, .. } => prefix,
+        }
+    }
+}
+
+/// A module used as `generator=`. The pages it generates show up in [`QueryResult::pages`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Generator {
+    List(List),
+    Prop(Prop),
+}
+
+impl From<List> for Generator {
+    fn from(list: List) -> Self {
+        Generator::List(list)
+    }
+}
+
+impl From<Prop> for Generator {
+    fn from(prop: Prop) -> Self {
+        Generator::Prop(prop)
+    }
+}
+
+impl Generator {
+    fn name(&self) -> &str {
+        match self {
+            Generator::List(l) => l.name(),
+            Generator::Prop(p) => p.name(),
+        }
+    }
+
+    fn prefix(&self) -> String {
+        match self {
+            Generator::List(l) => format!("g{}", l.prefix()),
+            Generator::Prop(p) => format!("g{}", p.prefix()),
+        }
+    }
+}
+
+/// Builder for `action=query` requests.
+///
+/// Continuation is handled automatically, and pages spread over multiple batches get merged.
+/// [`titles`](Self::titles), [`pageids`](Self::pageids) and [`revids`](Self::revids) get split into as few requests as the wiki's limits allow.
+/// # Example
+/// ```no_run
+/// # async fn test_query() -> Result<(), mw_tools::Error> {
+/// # let client = mw_tools::Client::new("")?;
+/// use mw_tools::api::query::{List, Prop, Query};
+///
+/// // Get the categories of every page in Category:Champions.
+/// let result = Query::new()
+///     .generator(List::CategoryMembers)
+///     .arg(List::CategoryMembers, "title", "Category:Champions")
+///     .prop(Prop::Categories)
+///     .limit("max")
+///     .fetch(&client)
+///     .await?;
+///
+/// for page in result.pages {
+///     println!("{}: {:?}", page.title, page.categories);
+/// }
+/// # Ok(())}
+/// ```
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct Query {
+    lists: Vec<List>,
+    props: Vec<Prop>,
+    generator: Option<Generator>,
+    parameters: Vec<(String, String)>,
+    // `titles`, `pageids` or `revids`, split into batches when sent.
+    values: Option<(&'static str, Vec<String>)>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a `list=` module.
+    pub fn list(mut self, list: List) -> Self {
+        self.lists.push(list);
+        self
+    }
+
+    /// Add a `prop=` module.
+    pub fn prop(mut self, prop: Prop) -> Self {
+        self.props.push(prop);
+        self
+    }
+
+    /// Use a module as generator.
+    pub fn generator<G: Into<Generator>>(mut self, generator: G) -> Self {
+        self.generator = Some(generator.into());
+        self
+    }
+
+    /// Pages to query props for. Mutually exclusive with [`generator`](Self::generator) and [`pageids`](Self::pageids).
+    pub fn titles<S: AsRef<str>>(mut self, titles: &[S]) -> Self {
+        let titles = titles.iter().map(|t| t.as_ref().to_string()).collect();
+        self.values = Some(("titles", titles));
+        self
+    }
+
+    /// Page ids to query props for.
+    pub fn pageids(mut self, ids: &[u64]) -> Self {
+        let ids = ids.iter().map(ToString::to_string).collect();
+        self.values = Some(("pageids", ids));
+        self
+    }
+
+    /// Revision ids to query props for, eg the content of old revisions with [`Prop::Revisions`].
+    pub fn revids(mut self, ids: &[u64]) -> Self {
+        let ids = ids.iter().map(ToString::to_string).collect();
+        self.values = Some(("revids", ids));
+        self
+    }
+
+    /// Resolve redirects of the given titles, page ids or generated pages.
+    pub fn redirects(self) -> Self {
+        self.param("redirects", "1")
+    }
+
+    /// Set the limit of every added module, eg `"max"` or `"50"`.
+    ///
+    /// Call this after adding the modules.
+    pub fn limit<S: AsRef<str>>(self, limit: S) -> Self {
+        self.for_each_module("limit", limit.as_ref())
+    }
+
+    /// Restrict every added module to the given namespaces.
+    ///
+    /// Call this after adding the modules.
+    pub fn namespace(self, namespaces: &[i32]) -> Self {
+        let namespaces: Vec<String> = namespaces.iter().map(ToString::to_string).collect();
+        self.for_each_module("namespace", &namespaces.join("|"))
+    }
+
+    /// Set a module specific argument without its prefix, eg `.arg(List::CategoryMembers, "title", "Category:X")`.
+    ///
+    /// If the module is used as the generator, the generator prefix gets used.
+    pub fn arg<M, V>(self, module: M, name: &str, value: V) -> Self
+    where
+        M: Into<Generator>,
+        V: Into<String>,
+    {
+        let module = module.into();
+        let key = if self.generator.as_ref() == Some(&module) {
+            format!("{}{}", module.prefix(), name)
+        } else {
+            match &module {
+                Generator::List(l) => format!("{}{}", l.prefix(), name),
+                Generator::Prop(p) => format!("{}{}", p.prefix(), name),
+            }
+        };
+        self.param(key, value)
+    }
+
+    /// Set a raw parameter.
+    pub fn param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        let key = key.into();
+        self.parameters.retain(|(k, _)| *k != key);
+        self.parameters.push((key, value.into()));
+        self
+    }
+
+    fn for_each_module(mut self, name: &str, value: &str) -> Self {
+        let mut prefixes: Vec<String> = self
+            .lists
+            .iter()
+            .map(|l| l.prefix().to_string())
+            .chain(self.props.iter().map(|p| p.prefix().to_string()))
+            .collect();
+        if let Some(g) = &self.generator {
+            prefixes.push(g.prefix());
+        }
+        for prefix in prefixes {
+            self = self.param(format!("{}{}", prefix, name), value);
+        }
+        self
+    }
+
+    fn build(&self) -> Vec<(String, String)> {
+        let mut parameters = Vec::new();
+        if !self.lists.is_empty() {
+            let lists: Vec<&str> = self.lists.iter().map(List::name).collect();
+            parameters.push(("list".to_string(), lists.join("|")));
+        }
+        if !self.props.is_empty() {
+            let props: Vec<&str> = self.props.iter().map(Prop::name).collect();
+            parameters.push(("prop".to_string(), props.join("|")));
+        }
+        if let Some(generator) = &self.generator {
+            parameters.push(("generator".to_string(), generator.name().to_string()));
+        }
+        parameters.extend(self.parameters.iter().cloned());
+        parameters
+    }
+
+    // The parameters of every request needed to send all titles or page ids.
+    async fn build_batches(&self, client: &Client) -> Result<Vec<Vec<(String, String)>>, Error> {
+        let Some((name, values)) = &self.values else {
+            return Ok(vec![self.build()]);
+        };
+
+        Ok(client
+            .batches(values)
+            .await?
+            .map(|chunk| {
+                let mut parameters = self.build();
+                parameters.push((name.to_string(), chunk.join("|")));
+                parameters
+            })
+            .collect())
+    }
+
+    /// Stream the raw batches, without merging them.
+    pub fn stream<'a>(
+        &self,
+        client: &'a Client,
+    ) -> impl Stream<Item = Result<QueryResult, Error>> + 'a {
+        let query = self.clone();
+        stream::once(async move { query.build_batches(client).await })
+            .map_ok(move |requests| {
+                stream::iter(requests)
+                    .map(move |parameters| client.query_stream::<Batch>(&as_pairs(&parameters)))
+                    .flatten()
+            })
+            .try_flatten()
+            .map(|batch| {
+                let mut result = QueryResult::default();
+                result.merge(batch?, &mut HashMap::new())?;
+                Ok(result)
+            })
+    }
+
+    /// Fetch all batches and merge them into one result.
+    pub async fn fetch(&self, client: &Client) -> Result<QueryResult, Error> {
+        let mut result = QueryResult::default();
+        let mut index = HashMap::new();
+        for parameters in self.build_batches(client).await? {
+            let mut batches = std::pin::pin!(client.query_stream::<Batch>(&as_pairs(&parameters)));
+            while let Some(batch) = batches.try_next().await? {
+                result.merge(batch, &mut index)?;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+pub(crate) fn as_pairs(parameters: &[(String, String)]) -> Vec<(&str, &str)> {
+    parameters
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect()
+}
+
+/// Look up the current revision of `titles`, keyed by the titles as given.
+///
+/// Used to validate changes before making them, eg in dry-run mode. Titles the wiki considers invalid are left out.
+pub(crate) async fn current_revisions(
+    client: &Client,
+    titles: &[&str],
+    content: bool,
+) -> Result<HashMap<String, Page>, Error> {
+    let rvprop = if content {
+        "ids|timestamp|size|content"
+    } else {
+        "ids|timestamp|size"
+    };
+
+    let result = Query::new()
+        .prop(Prop::Revisions)
+        .arg(Prop::Revisions, "prop", rvprop)
+        .arg(Prop::Revisions, "slots", "main")
+        .titles(titles)
+        .fetch(client)
+        .await?;
+
+    let mut pages = HashMap::new();
+    for title in titles {
+        let normalized = result
+            .normalized
+            .iter()
+            .find(|m| m.from == *title)
+            .map_or(*title, |m| m.to.as_str());
+        if let Some(page) = result
+            .pages
+            .iter()
+            .find(|p| p.title == normalized && !p.invalid)
+        {
+            pages.insert(title.to_string(), page.clone());
+        }
+    }
+
+    Ok(pages)
+}
@@ -1,4 +1,11 @@
-use crate::{response::rename::Rename, Client, Error};
+use crate::{
+    api::{
+        query::current_revisions,
+        report::{ItemData, Outcome, Report},
+    },
+    response::rename::Rename,
+    Client, Error,
+};
 
 pub async fn rename(
     client: &Client,
@@ -6,7 +13,7 @@ pub async fn rename(
     to: Option<Destination>,
     prepend: Option<&str>,
     append: Option<&str>,
-) -> Result<(), Error> {
+) -> Result<Report, Error> {
     let mut actual_destination: Vec<String> = Vec::new();
 
     if let Some(to) = to {
@@ -39,44 +46,88 @@ pub async fn rename(
             if let Some(p) = &prepend {
                 x.insert_str(0, p);
             }
-            if let Some(a) = &prepend {
+            if let Some(a) = &append {
                 x.push_str(a);
             }
         }
     }
 
-    for (x, y) in from.iter().zip(actual_destination.iter()) {
-        let response: Result<Rename, Error> = client
-            .post(&[
-                ("action", "move"),
-                ("from", x),
-                ("to", y),
-                ("reason", "automated action"),
-                ("movetalk", ""),
-                ("movesubpages", ""),
-                ("ignorewarnings", ""),
-            ])
-            .await;
+    client.require_rights(&["move"]).await?;
+    let site_info = client.site_info().await?;
+    // Subpages are only moved along if the account may do so.
+    let move_subpages = client.user_info().await?.has_right("move-subpages");
+
+    let pages = if client.is_dry_run() {
+        let titles: Vec<&str> = from
+            .iter()
+            .chain(actual_destination.iter())
+            .map(String::as_str)
+            .collect();
+        Some(current_revisions(client, &titles, false).await?)
+    } else {
+        None
+    };
+
+    let mut report = Report::default();
+    let mut tracker = client.track("rename", Some(from.len()));
+    for (i, (x, y)) in from.iter().zip(actual_destination.iter()).enumerate() {
+        if site_info.normalize_title(x) == site_info.normalize_title(y) {
+            report.push(&mut tracker, x, Outcome::Skipped("same title".to_string()));
+            continue;
+        }
+
+        if let Some(pages) = &pages {
+            let exists = |title: &str| pages.get(title).is_some_and(|p| !p.missing);
+            let description = if !exists(x) {
+                "page doesn't exist, the move would fail".to_string()
+            } else if exists(y) {
+                format!("\"{}\" already exists, the move would fail", y)
+            } else {
+                format!("would move to \"{}\"", y)
+            };
+            report.dry_run(client, &mut tracker, "move", x, description);
+            continue;
+        }
+
+        let mut parameters = vec![
+            ("action", "move"),
+            ("from", x.as_str()),
+            ("to", y.as_str()),
+            ("reason", "automated action"),
+            ("movetalk", ""),
+            ("ignorewarnings", ""),
+        ];
+        if move_subpages {
+            parameters.push(("movesubpages", ""));
+        }
+        let response: Result<Rename, Error> = client.post(&parameters).await;
 
         log::debug!("{:?}", response);
 
         match response {
-            Ok(m) => println!("{} => MOVED TO => {}", m.rename.from, m.rename.to),
-            Err(err) => println!(
-                "Error moving {} to {}: {}\nProceeding with next pages...",
-                x,
-                y,
-                match err {
-                    Error::MediaWikiApi(err) => format!("{} - {}", err.code, err.description),
-                    _ => err.code().to_string(),
-                }
-            ),
+            Ok(m) => {
+                log::info!("moved \"{}\" to \"{}\"", m.rename.from, m.rename.to);
+                report.push(
+                    &mut tracker,
+                    x,
+                    Outcome::Success(ItemData {
+                        new_title: Some(m.rename.to),
+                        ..ItemData::default()
+                    }),
+                );
+            }
+            Err(Error::Cancelled) => {
+                report.push_cancelled(from[i..].iter().map(String::as_str));
+                break;
+            }
+            Err(err) => {
+                log::error!("moving \"{}\" to \"{}\" failed. reason: {}", x, y, err);
+                report.push(&mut tracker, x, Outcome::Failed(err));
+            }
         }
-
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     }
 
-    Ok(())
+    Ok(report)
 }
 
 #[derive(Debug)]
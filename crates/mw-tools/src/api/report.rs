@@ -0,0 +1,174 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{client::Tracker, Client, Error};
+
+/// Result of a bulk operation like [`delete`](crate::api::delete::delete), with one item per title in input order.
+///
+/// Failing titles don't stop the operation, so check [`failed`](Self::failed) or [`is_success`](Self::is_success).
+/// If the operation gets cancelled, the remaining titles are reported as [`Cancelled`](Outcome::Cancelled).
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub items: Vec<ItemReport>,
+}
+
+/// Result for a single title of a bulk operation.
+#[derive(Debug, Serialize)]
+pub struct ItemReport {
+    pub title: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", content = "details", rename_all = "camelCase")]
+pub enum Outcome {
+    /// The action went through.
+    Success(ItemData),
+    /// Nothing to do for this title, eg because it would be moved onto itself.
+    Skipped(String),
+    /// The client is in dry-run mode. Contains what would happen.
+    DryRun(String),
+    /// The API or the connection returned an error.
+    Failed(Error),
+    /// The operation got cancelled before reaching this title.
+    Cancelled,
+}
+
+/// Data the API returned about a successful action. Which fields are set depends on the action.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemData {
+    /// Id of the revision the action created. For a null edit, the current revision of the page.
+    pub revid: Option<u64>,
+    /// Id of the log entry, eg for a deletion.
+    pub logid: Option<u64>,
+    /// Title after a move.
+    pub new_title: Option<String>,
+}
+
+impl Report {
+    // Add the outcome of a title and report it as progress.
+    pub(crate) fn push<S: Into<String>>(
+        &mut self,
+        tracker: &mut Tracker<'_>,
+        title: S,
+        outcome: Outcome,
+    ) {
+        let title = title.into();
+        match &outcome {
+            Outcome::Failed(err) => tracker.failed(&title, err),
+            _ => tracker.done(&title),
+        }
+        self.items.push(ItemReport { title, outcome });
+    }
+
+    // Report a change skipped in dry-run mode, to the handler of the client and in this report.
+    pub(crate) fn dry_run<S: Into<String>>(
+        &mut self,
+        client: &Client,
+        tracker: &mut Tracker<'_>,
+        action: &str,
+        title: &str,
+        description: S,
+    ) {
+        let description = description.into();
+        client.report_change(action, title, description.as_str());
+        self.push(tracker, title, Outcome::DryRun(description));
+    }
+
+    // Mark `titles` as cancelled because the operation stopped before reaching them.
+    pub(crate) fn push_cancelled<'t, I: IntoIterator<Item = &'t str>>(&mut self, titles: I) {
+        self.items
+            .extend(titles.into_iter().map(|title| ItemReport {
+                title: title.to_string(),
+                outcome: Outcome::Cancelled,
+            }));
+    }
+
+    pub fn succeeded(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.outcome, Outcome::Success(_)))
+    }
+
+    pub fn skipped(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.outcome, Outcome::Skipped(_)))
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.outcome, Outcome::Failed(_)))
+    }
+
+    pub fn cancelled(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.outcome, Outcome::Cancelled))
+    }
+
+    /// Whether no title failed and the operation wasn't cancelled.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.failed().next().is_none() && self.cancelled().next().is_none()
+    }
+}
+
+/// A summary like `3 succeeded, 1 skipped, 0 failed`.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} succeeded, {} skipped, {} failed",
+            self.succeeded().count(),
+            self.skipped().count(),
+            self.failed().count()
+        )?;
+        let planned = self
+            .items
+            .iter()
+            .filter(|i| matches!(i.outcome, Outcome::DryRun(_)))
+            .count();
+        if planned > 0 {
+            write!(f, ", {} planned in dry-run mode", planned)?;
+        }
+        let cancelled = self.cancelled().count();
+        if cancelled > 0 {
+            write!(f, ", {} cancelled", cancelled)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ItemReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.title, self.outcome)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Success(data) => {
+                f.write_str("done")?;
+                if let Some(title) = &data.new_title {
+                    write!(f, ", now \"{}\"", title)?;
+                }
+                if let Some(revid) = data.revid {
+                    write!(f, ", revision {}", revid)?;
+                }
+                if let Some(logid) = data.logid {
+                    write!(f, ", log entry {}", logid)?;
+                }
+                Ok(())
+            }
+            Outcome::Skipped(reason) => write!(f, "skipped, {}", reason),
+            Outcome::DryRun(description) => write!(f, "dry run, {}", description),
+            Outcome::Failed(err) => write!(f, "failed, {}", err),
+            Outcome::Cancelled => f.write_str("cancelled"),
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use std::path::Path;
 
-use crate::{response::upload::Upload, Client, Error};
+use crate::{api::query::current_revisions, response::upload::Upload, Client, Error};
 
+/// Upload `file` with `text` as the description page. Returns the result reported by the API, usually `Success`.
+///
+/// In dry-run mode, the upload is reported instead and the result is `DryRun`.
 pub async fn upload<P: AsRef<Path>>(
     client: &Client,
     file: P,
@@ -16,8 +19,26 @@ pub async fn upload<P: AsRef<Path>>(
         .ok_or_else(|| Error::InvalidInput(format!("Invalid file name: {:?}", file.display())))?
         .to_string();
 
+    client.require_rights(&["upload"]).await?;
+
     let file_content = tokio::fs::read(file).await?;
-    let part = reqwest::multipart::Part::bytes(file_content).file_name(file_name.clone());
+
+    if client.is_dry_run() {
+        let title = format!("File:{}", file_name);
+        let pages = current_revisions(client, &[&title], false).await?;
+        let description = match pages.get(&title) {
+            None => "invalid file name, the upload would fail".to_string(),
+            Some(page) if page.missing => {
+                format!("would upload {} bytes", file_content.len())
+            }
+            Some(_) => format!(
+                "would overwrite the existing file with {} bytes",
+                file_content.len()
+            ),
+        };
+        client.report_change("upload", &title, description);
+        return Ok("DryRun".to_string());
+    }
 
     let response: Upload = client
         .send_multipart(
@@ -27,10 +48,9 @@ pub async fn upload<P: AsRef<Path>>(
                 ("filename", &file_name),
                 ("ignorewarnings", ""),
             ],
-            part,
+            &file_name,
+            &file_content,
         )
-        .await?
-        .json()
         .await?;
 
     Ok(response.upload.result)
@@ -41,8 +61,18 @@ pub async fn upload_multiple<P: AsRef<Path>>(
     files: &[P],
     text: Option<&str>,
 ) -> Result<(), Error> {
+    client.require_rights(&["upload"]).await?;
+    let mut tracker = client.track("upload", Some(files.len()));
+
     for file in files {
-        upload(client, file, text).await?;
+        let title = file.as_ref().display().to_string();
+        match upload(client, file, text).await {
+            Ok(_) => tracker.done(&title),
+            Err(err) => {
+                tracker.failed(&title, &err);
+                return Err(err);
+            }
+        }
     }
 
     Ok(())
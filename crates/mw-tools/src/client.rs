@@ -1,21 +1,103 @@
-use reqwest::{Client as ReqwestClient, Response};
-use serde::de::DeserializeOwned;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
+    time::Duration,
+};
+
+use reqwest::{Client as ReqwestClient, Method};
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    response::{login::Login, token::Token, Ignore},
+    response::{login::Login, token::Token, userinfo::UserInfo, Ignore, Warning},
     Error,
 };
 
+pub use builder::ClientBuilder;
+pub use clientlogin::{LoginContinuation, LoginStep};
+pub use dry_run::PlannedChange;
+pub(crate) use progress::Tracker;
+pub use progress::{Progress, ProgressEvent};
+pub use rate_limit::RateLimit;
+pub use retry::RetryPolicy;
+pub use saved_session::SavedSession;
+pub use site_info::{Extension, MagicWord, Namespace, SiteInfo};
+pub use transport::{
+    ApiRequest, MockTransport, ReqwestTransport, Transport, TransportResponse, UploadFile,
+};
+
+mod builder;
+mod cancel;
+mod clientlogin;
+mod dry_run;
+mod progress;
+mod rate_limit;
+mod retry;
+mod saved_session;
+mod site_info;
+mod stream;
+#[cfg(test)]
+mod tests;
+mod transport;
+mod user_info;
+mod warnings;
+
 /// A wrapper around [`reqwest::Client`] to interact with the mediawiki API.
 ///
 /// Intended to use with functions provided in the [`api`](crate::api) module.
+///
+/// Clones share the login session, just like clones of [`reqwest::Client`] share their cookies.
 #[derive(Clone, Debug)]
 pub struct Client {
     client: ReqwestClient,
+    transport: Arc<dyn Transport>,
     url: String,
+    session: Arc<RwLock<Session>>,
+    retry: RetryPolicy,
+    limiter: Arc<rate_limit::RateLimiter>,
+    pending_login: Option<LoginContinuation>,
+    warning_handler: Option<warnings::WarningHandler>,
+    dry_run: bool,
+    dry_run_handler: Option<dry_run::DryRunHandler>,
+    progress: Option<progress::ProgressObserver>,
+    cancel: Option<CancellationToken>,
+    refresh: Arc<Refresh>,
+}
+
+// Login state shared between clones of a Client.
+#[derive(Debug, Default)]
+struct Session {
     csrf_token: String,
+    // Remembered to log in again if the session expires.
+    credentials: Option<Credentials>,
+    // Depends on the wiki and the account, so it gets reset together with the session.
+    site_info: Option<Arc<SiteInfo>>,
+    user_info: Option<Arc<UserInfo>>,
 }
 
+// Serializes session refreshes of all clones, so concurrent requests failing with the same expired session
+// log in only once. Kept apart from `Session` because that gets reset on logout.
+#[derive(Debug, Default)]
+struct Refresh {
+    lock: tokio::sync::Mutex<()>,
+    // Number of refreshes so far, to tell whether one happened since a request was sent.
+    count: AtomicU64,
+}
+
+#[derive(Clone, Debug)]
+enum Credentials {
+    Password { username: String, password: String },
+    // OAuth 2 access token, sent as a bearer token with every request.
+    OAuth(String),
+    // Logged in via clientlogin. Can't be repeated without user interaction.
+    Interactive,
+}
+
+// GET requests with longer URLs are sent as POST instead. Servers commonly reject request lines above 8 KiB.
+const MAX_URL_LENGTH: usize = 8000;
+
 impl AsRef<Client> for Client {
     fn as_ref(&self) -> &Self {
         self
@@ -23,10 +105,11 @@ impl AsRef<Client> for Client {
 }
 
 #[derive(serde::Deserialize)]
+// Failure goes first, otherwise loosely typed responses like `serde_json::Value` would swallow errors.
 #[serde(untagged)]
 enum ApiResponse<T> {
-    Success(T),
     Failure { errors: Vec<crate::response::Error> },
+    Success(T),
 }
 
 impl Client {
@@ -36,46 +119,117 @@ impl Client {
     ///
     /// Either way, there needs to be a correct URL present before logging in!
     ///
+    /// Use [`builder`](Self::builder) to configure timeouts, proxies, the user agent and more.
+    ///
     /// # Errors
     /// Just like [`reqwest::ClientBuilder`], this method fails if a TLS backend cannot be initialized, or the resolver cannot load the system configuration.
     pub fn new<S: Into<String>>(url: S) -> Result<Self, Error> {
-        Ok(Self {
-            client: ReqwestClient::builder()
-                .cookie_store(true)
-                .user_agent(concat!(
-                    env!("CARGO_PKG_NAME"),
-                    "/",
-                    env!("CARGO_PKG_VERSION"),
-                    " using rust/reqwest",
-                ))
-                .build()?,
-            url: url.into(),
-            csrf_token: String::new(),
-        })
+        Self::builder(url).build()
+    }
+
+    /// Create a [`ClientBuilder`] to configure the client before constructing it.
+    pub fn builder<S: Into<String>>(url: S) -> ClientBuilder {
+        ClientBuilder::new(url.into())
     }
 
     /// Set the URL to the mediawiki API (pointing to api.php, including the scheme).
     pub fn set_url<S: Into<String>>(&mut self, url: S) {
         self.url = url.into();
+        let mut session = self.session_mut();
+        session.site_info = None;
+        session.user_info = None;
+        self.limiter.reset();
+    }
+
+    /// Replace the [`RetryPolicy`] used for all following requests.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Get the currently used [`RetryPolicy`].
+    #[must_use]
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Replace the [`RateLimit`] used for all following requests.
+    ///
+    /// Clones made before this call keep sharing the previous limits.
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        let limiter = rate_limit::RateLimiter::new(limit);
+        if let Some(info) = &self.session().user_info {
+            limiter.update(info);
+        }
+        self.limiter = Arc::new(limiter);
+    }
+
+    /// Get the currently used [`RateLimit`].
+    #[must_use]
+    pub fn rate_limit(&self) -> &RateLimit {
+        self.limiter.config()
     }
 
     /// log into the mediawiki API.
     ///
-    /// If successful, this method also requests an edit token which is needed for some endpoints.
+    /// If successful, this method also requests an edit token which is needed for some endpoints,
+    /// and loads the [`user_info`](Self::user_info) of the account.
+    ///
+    /// The credentials are remembered to transparently log in again if the session expires.
     pub async fn login(&mut self, username: &str, password: &str) -> Result<(), Error> {
-        let json: Token = self
-            .get(&[("action", "query"), ("meta", "tokens"), ("type", "login")])
-            .await?;
+        self.login_with(username, password).await?;
+        {
+            let mut session = self.session_mut();
+            session.credentials = Some(Credentials::Password {
+                username: username.to_string(),
+                password: password.to_string(),
+            });
+            session.site_info = None;
+        }
 
-        log::debug!("this should contain the requested login token: {:?}", &json);
+        self.load_user_info().await?;
 
-        let token = match json.query.tokens.logintoken {
-            Some(t) => t,
-            None => return Err(Error::TokenNotFound(format!("{:?}", json))),
+        Ok(())
+    }
+
+    /// Remember credentials to log in again once the session expires, without logging in now.
+    ///
+    /// Useful after [`restore_session`](Self::restore_session).
+    pub fn set_credentials(&mut self, username: &str, password: &str) {
+        self.session_mut().credentials = Some(Credentials::Password {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+    }
+
+    /// Authenticate with an OAuth 2 access token of an owner-only consumer.
+    ///
+    /// Create one via `Special:OAuthConsumerRegistration/propose/oauth2` on the wiki.
+    /// The token is sent as a bearer token with every following request, which makes the API treat them as logged in.
+    ///
+    /// If successful, this method also requests an edit token which is needed for some endpoints.
+    pub async fn login_oauth(&mut self, access_token: &str) -> Result<(), Error> {
+        *self.session_mut() = Session {
+            credentials: Some(Credentials::OAuth(access_token.to_string())),
+            ..Session::default()
         };
 
+        if let Err(err) = self.request_csrf_token().await {
+            *self.session_mut() = Session::default();
+            self.limiter.reset();
+            return Err(err);
+        }
+
+        self.load_user_info().await?;
+
+        Ok(())
+    }
+
+    // `&self` version of `login` used to refresh expired sessions.
+    async fn login_with(&self, username: &str, password: &str) -> Result<(), Error> {
+        let token = self.login_token().await?;
+
         let res: Login = self
-            .post(&[
+            .post_form(&[
                 ("action", "login"),
                 ("lgname", username),
                 ("lgpassword", password),
@@ -98,17 +252,24 @@ impl Client {
     ///
     /// You generally don't need to call this, except if you want to switch the wiki or user without creating a new Client.
     pub async fn logout(&mut self) -> Result<(), Error> {
-        self.post::<Ignore>(&[("action", "logout"), ("token", &self.csrf_token)])
-            .await?;
+        // OAuth sessions only live as long as we send the access token.
+        if !matches!(self.session().credentials, Some(Credentials::OAuth(_))) {
+            let token = self.csrf_token();
+            self.post_form::<Ignore>(&[("action", "logout"), ("token", &token)])
+                .await?;
+        }
 
         log::debug!("logout successful");
 
-        self.csrf_token.clear();
+        *self.session_mut() = Session::default();
+        self.limiter.reset();
 
         Ok(())
     }
 
     /// Get a reference to the inner [`reqwest::Client`].
+    ///
+    /// Meant for requests outside of the API, like downloading files. API requests go through the [`Transport`].
     #[must_use]
     pub fn client(&self) -> &ReqwestClient {
         &self.client
@@ -117,13 +278,17 @@ impl Client {
     /// Check if the client is logged into the API.
     #[must_use]
     pub fn is_online(&self) -> bool {
-        !self.csrf_token.is_empty()
+        !self.csrf_token().is_empty()
     }
 
     /// Send a GET request with query parameters.
     /// You need to specify a response type which implements [`serde::de::DeserializeOwned`].
     ///
     /// If you don't care about the response, use [`serde::de::IgnoredAny`].
+    ///
+    /// Transient failures are retried according to the client's [`RetryPolicy`].
+    ///
+    /// If the URL would get too long, eg because of hundreds of titles, the request is sent as POST instead.
     /// # Example
     /// ```no_run
     /// # async fn test_get() -> Result<(), mw_tools::Error> {
@@ -135,37 +300,11 @@ impl Client {
     /// # Ok(())}
     /// ```
     pub async fn get<T: DeserializeOwned>(&self, parameters: &[(&str, &str)]) -> Result<T, Error> {
-        loop {
-            // It's fine to create a new request every iteration, because we almost never need a second one.
-            let res: ApiResponse<T> = self
-                .client
-                .get(&self.url)
-                .query(&[
-                    ("format", "json"),
-                    ("formatversion", "2"),
-                    ("errorformat", "plaintext"),
-                ])
-                .query(parameters)
-                .send()
-                .await
-                .and_then(|res| res.error_for_status())
-                .map_err(Error::from)?
-                .json()
-                .await
-                .map_err(Error::from)?;
-            match res {
-                ApiResponse::Success(r) => return Ok(r),
-                ApiResponse::Failure { mut errors } => {
-                    let err = errors.remove(0);
-                    if &err.code == "ratelimited" {
-                        log::warn!("Rate limited! Retrying after 15 seconds...");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(15)).await;
-                        continue;
-                    }
-                    return Err(Error::MediaWikiApi(err));
-                }
-            };
+        let mut request = self.api_request(Method::GET, parameters);
+        if request.url_length() > MAX_URL_LENGTH {
+            request.method = Method::POST;
         }
+        self.send(request).await
     }
 
     /// Send a POST request with parameters added as a form body.
@@ -173,6 +312,12 @@ impl Client {
     /// You need to specify a response type which implements [`serde::de::DeserializeOwned`].
     ///
     /// If you don't care about the response, use [`serde::de::IgnoredAny`].
+    ///
+    /// Transient failures are retried according to the client's [`RetryPolicy`].
+    ///
+    /// The csrf token gets added automatically for actions that need it.
+    /// If the API rejects the token or the session expired, the client logs in again with the remembered credentials
+    /// and replays the request once.
     /// # Example
     /// ```no_run
     /// # async fn test_post() -> Result<(), mw_tools::Error> {
@@ -186,50 +331,177 @@ impl Client {
     /// # Ok(())}
     /// ```
     pub async fn post<T: DeserializeOwned>(&self, parameters: &[(&str, &str)]) -> Result<T, Error> {
-        let parameters = if parameters
+        if !parameters
             .iter()
             .any(|(x, y)| *x == "action" && ["delete", "edit", "move", "upload"].contains(y))
         {
-            [parameters, &[("token", self.csrf_token.as_str())]].concat()
+            return self.post_form(parameters).await;
+        }
+
+        self.with_session(|token| async move {
+            let parameters = [parameters, &self.session_parameters(&token)].concat();
+            self.post_form(&parameters).await
+        })
+        .await
+    }
+
+    // send a POST request without touching the csrf token
+    async fn post_form<T: DeserializeOwned>(
+        &self,
+        parameters: &[(&str, &str)],
+    ) -> Result<T, Error> {
+        self.send(self.api_request(Method::POST, parameters)).await
+    }
+
+    // run a request which needs the csrf token. Refreshes the session and replays the request once if it expired.
+    // Requests which failed while another one refreshed the session just replay with the new one.
+    async fn with_session<T, F, Fut>(&self, request: F) -> Result<T, Error>
+    where
+        F: Fn(String) -> Fut,
+        Fut: std::future::Future<Output = Result<T, Error>>,
+    {
+        let seen = self.refresh.count.load(Ordering::Acquire);
+        match request(self.csrf_token()).await {
+            Err(Error::MediaWikiApi(err)) if err.code.is_session_error() => {
+                let guard = self.refresh.lock.lock().await;
+                if self.refresh.count.load(Ordering::Acquire) == seen {
+                    log::warn!("{} Refreshing session and trying again...", err);
+                    self.refresh_session().await?;
+                    self.refresh.count.fetch_add(1, Ordering::Release);
+                } else {
+                    log::debug!("{} Session got refreshed meanwhile, trying again...", err);
+                }
+                drop(guard);
+                request(self.csrf_token()).await
+            }
+            res => res,
+        }
+    }
+
+    // the token and, if logged in with credentials, the `assert` parameter to detect expired sessions
+    fn session_parameters<'a>(&self, token: &'a str) -> Vec<(&'static str, &'a str)> {
+        if self.session().credentials.is_some() {
+            vec![("token", token), ("assert", "user")]
         } else {
-            parameters.to_vec()
+            vec![("token", token)]
+        }
+    }
+
+    // log in again with the remembered credentials, or just get a new token if there are none
+    async fn refresh_session(&self) -> Result<(), Error> {
+        let credentials = self.session().credentials.clone();
+        match credentials {
+            Some(Credentials::Password { username, password }) => {
+                self.login_with(&username, &password).await
+            }
+            Some(Credentials::OAuth(_) | Credentials::Interactive) | None => {
+                self.request_csrf_token().await
+            }
+        }
+    }
+
+    fn session(&self) -> RwLockReadGuard<'_, Session> {
+        self.session.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn session_mut(&self) -> RwLockWriteGuard<'_, Session> {
+        self.session.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn csrf_token(&self) -> String {
+        self.session().csrf_token.clone()
+    }
+
+    // create a request to api.php with the parameters every request needs
+    fn api_request(&self, method: Method, parameters: &[(&str, &str)]) -> ApiRequest {
+        let mut all = vec![
+            ("format".to_string(), "json".to_string()),
+            ("formatversion".to_string(), "2".to_string()),
+            ("errorformat".to_string(), "plaintext".to_string()),
+        ];
+        if let Some(maxlag) = self.retry.maxlag {
+            all.push(("maxlag".to_string(), maxlag.to_string()));
+        }
+        all.extend(
+            parameters
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string())),
+        );
+
+        let bearer_token = match &self.session().credentials {
+            Some(Credentials::OAuth(token)) => Some(token.clone()),
+            _ => None,
         };
 
+        ApiRequest {
+            method,
+            url: self.url.clone(),
+            parameters: all,
+            bearer_token,
+            file: None,
+        }
+    }
+
+    // send `request` until it succeeds, fails permanently or the retry policy gives up
+    async fn send<T: DeserializeOwned>(&self, request: ApiRequest) -> Result<T, Error> {
+        let action = request.parameter("action").unwrap_or_default().to_string();
+        let write = rate_limit::is_write(&action);
+        let mut attempt = 0;
         loop {
-            // It's fine to create a new request every iteration, because we almost never need a second try
-            let res: ApiResponse<T> = self
-                .client
-                .post(&self.url)
-                .query(&[
-                    ("format", "json"),
-                    ("formatversion", "2"),
-                    ("errorformat", "plaintext"),
-                ])
-                .form(&parameters)
-                .send()
-                .await
-                .and_then(|res| res.error_for_status())
-                .map_err(Error::from)?
-                .json()
-                .await
-                .map_err(Error::from)?;
-            match res {
-                ApiResponse::Success(r) => return Ok(r),
-                ApiResponse::Failure { mut errors } => {
-                    let err = errors.remove(0);
-                    if &err.code == "ratelimited" {
-                        log::warn!("Rate limited! Retrying after 15 seconds...");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(15)).await;
-                        continue;
-                    }
-                    return Err(Error::MediaWikiApi(err));
+            attempt += 1;
+            let response = {
+                let _permit = self
+                    .unless_cancelled(self.limiter.acquire(&action, write))
+                    .await?;
+                self.transport.send(request.clone()).await
+            };
+            let (err, retry_after) = match parse_response(response) {
+                Ok((r, warnings)) => {
+                    self.report_warnings(&warnings);
+                    return Ok(r);
                 }
+                Err(Failure::Fatal(err)) => return Err(err),
+                Err(Failure::Transient(err, retry_after)) => (err, retry_after),
             };
+
+            if attempt >= self.retry.max_attempts {
+                if attempt == 1 {
+                    return Err(err);
+                }
+                return Err(Error::RetriesExhausted {
+                    attempts: attempt,
+                    last: Box::new(err),
+                });
+            }
+
+            let delay = retry_after.unwrap_or_else(|| self.retry.backoff(attempt));
+            log::warn!(
+                "{} Retrying in {:.1} seconds ({}/{})...",
+                err,
+                delay.as_secs_f64(),
+                attempt,
+                self.retry.max_attempts
+            );
+            self.sleep(delay).await?;
+        }
+    }
+
+    // get a token for action=login and action=clientlogin
+    async fn login_token(&self) -> Result<String, Error> {
+        let json: Token = self
+            .get(&[("action", "query"), ("meta", "tokens"), ("type", "login")])
+            .await?;
+
+        log::debug!("this should contain the requested login token: {:?}", &json);
+
+        match json.query.tokens.logintoken {
+            Some(t) => Ok(t),
+            None => Err(Error::TokenNotFound(format!("{:?}", json))),
         }
     }
 
     // get a new edit token
-    async fn request_csrf_token(&mut self) -> Result<(), Error> {
+    async fn request_csrf_token(&self) -> Result<(), Error> {
         let res: Token = self
             .get(&[("action", "query"), ("meta", "tokens"), ("type", "csrf")])
             .await?;
@@ -242,7 +514,7 @@ impl Client {
                     "token was '+\\\\' aka empty".to_string(),
                 ));
             }
-            self.csrf_token = token;
+            self.session_mut().csrf_token = token;
         } else {
             return Err(Error::TokenNotFound(format!("{:?}", res)));
         }
@@ -251,30 +523,82 @@ impl Client {
     }
 
     // upload a file via a multipart/form-data request
-    pub(crate) async fn send_multipart(
+    pub(crate) async fn send_multipart<T: DeserializeOwned>(
         &self,
         parameters: &[(&str, &str)],
-        file_part: reqwest::multipart::Part,
-    ) -> Result<Response, Error> {
-        let mut form = reqwest::multipart::Form::new().part("file", file_part);
-        let parameters = [
-            parameters,
-            &[
-                ("format", "json"),
-                ("formatversion", "2"),
-                ("errorformat", "plaintext"),
-                ("token", &self.csrf_token),
-            ],
-        ]
-        .concat();
-        for (k, v) in parameters {
-            form = form.text(k.to_string(), v.to_string());
+        file_name: &str,
+        file_content: &[u8],
+    ) -> Result<T, Error> {
+        self.with_session(|token| async move {
+            let parameters = [parameters, &self.session_parameters(&token)].concat();
+            let mut request = self.api_request(Method::POST, &parameters);
+            request.file = Some(UploadFile {
+                name: file_name.to_string(),
+                content: file_content.to_vec(),
+            });
+            self.send(request).await
+        })
+        .await
+    }
+}
+
+// Outcome of a single failed attempt.
+enum Failure {
+    // Retrying won't help.
+    Fatal(Error),
+    // Retrying might help. Contains the delay requested by the server, if any.
+    Transient(Error, Option<Duration>),
+}
+
+fn parse_response<T: DeserializeOwned>(
+    response: Result<TransportResponse, Error>,
+) -> Result<(T, Vec<Warning>), Failure> {
+    let res = match response {
+        Ok(res) => res,
+        Err(err @ Error::Timeout(_)) => return Err(Failure::Transient(err, None)),
+        Err(err) => return Err(Failure::Fatal(err)),
+    };
+
+    if !res.status.is_success() {
+        let err = Error::StatusCode(res.status.to_string());
+        if retry::is_retryable_status(res.status) {
+            return Err(Failure::Transient(err, res.retry_after));
+        }
+        return Err(Failure::Fatal(err));
+    }
+
+    let retry_after = res.retry_after;
+    let mut value: serde_json::Value = serde_json::from_slice(&res.body)
+        .map_err(|e| Failure::Fatal(Error::ParsingFailed(e.to_string())))?;
+    // Malformed warnings shouldn't fail an otherwise successful request, but they shouldn't vanish silently either.
+    // They get removed, so responses with a `warnings` field like query batches don't fail on them.
+    let warnings = match value.get("warnings").map(Vec::<Warning>::deserialize) {
+        Some(Ok(warnings)) => warnings,
+        Some(Err(e)) => {
+            let raw = value.as_object_mut().and_then(|o| o.remove("warnings"));
+            log::warn!("couldn't parse warnings: {}, raw: {:?}", e, raw);
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+    let res = ApiResponse::<T>::deserialize(value)
+        .map_err(|e| Failure::Fatal(Error::ParsingFailed(e.to_string())))?;
+
+    match res {
+        ApiResponse::Success(r) => Ok((r, warnings)),
+        ApiResponse::Failure { errors } => {
+            let mut errors = errors.into_iter();
+            let Some(mut err) = errors.next() else {
+                return Err(Failure::Fatal(Error::ParsingFailed(
+                    "response contains an empty errors array".to_string(),
+                )));
+            };
+            err.additional = errors.collect();
+            if err.retryable() {
+                Err(Failure::Transient(Error::MediaWikiApi(err), retry_after))
+            } else {
+                Err(Failure::Fatal(Error::MediaWikiApi(err)))
+            }
         }
-        self.client
-            .post(&self.url)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(Into::into)
     }
 }
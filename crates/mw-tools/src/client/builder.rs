@@ -0,0 +1,237 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use reqwest::{
+    cookie::Jar,
+    header::{HeaderMap, HeaderName, HeaderValue},
+};
+
+use super::{rate_limit::RateLimiter, Client, RateLimit, ReqwestTransport, RetryPolicy, Transport};
+use crate::Error;
+
+const DEFAULT_USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " using rust/reqwest",
+);
+
+/// A builder to configure the HTTP client used by [`Client`].
+///
+/// Created via [`Client::builder`]. Invalid values are reported by [`build`](Self::build).
+/// # Example
+/// ```no_run
+/// # fn test_builder() -> Result<(), mw_tools::Error> {
+/// use std::time::Duration;
+///
+/// let client = mw_tools::Client::builder("https://community.fandom.com/api.php")
+///     .user_agent("MyBot/1.0 (https://example.org/MyBot; mybot@example.org)")
+///     .connect_timeout(Duration::from_secs(10))
+///     .proxy("http://proxy.example.org:8080")
+///     .build()?;
+/// # Ok(())}
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct ClientBuilder {
+    url: String,
+    user_agent: String,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    headers: Vec<(String, String)>,
+    root_certificates: Vec<Vec<u8>>,
+    http_version: HttpVersion,
+    retry: RetryPolicy,
+    rate_limit: RateLimit,
+    dry_run: bool,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+#[derive(Debug)]
+enum HttpVersion {
+    Auto,
+    Http1Only,
+    Http2PriorKnowledge,
+}
+
+impl ClientBuilder {
+    pub(crate) fn new(url: String) -> Self {
+        Self {
+            url,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            proxy: None,
+            headers: Vec::new(),
+            root_certificates: Vec::new(),
+            http_version: HttpVersion::Auto,
+            retry: RetryPolicy::default(),
+            rate_limit: RateLimit::default(),
+            dry_run: false,
+            transport: None,
+        }
+    }
+
+    /// Set the `User-Agent` header.
+    ///
+    /// Wikimedia's policy requires it to contain a way to contact you, eg `MyBot/1.0 (https://example.org; me@example.org)`.
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for each read operation while receiving a response.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Total timeout for a request, from connecting until the response body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Route all requests through a proxy, eg `http://proxy:8080` or `socks5://proxy:1080`.
+    ///
+    /// Without this, the system's proxy environment variables are respected.
+    pub fn proxy<S: Into<String>>(mut self, url: S) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Add a header that is sent with every request.
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Trust an additional root certificate in PEM format, eg for self-hosted wikis or TLS-intercepting proxies.
+    pub fn add_root_certificate_pem(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(pem.to_vec());
+        self
+    }
+
+    /// Only use HTTP/1.
+    pub fn http1_only(mut self) -> Self {
+        self.http_version = HttpVersion::Http1Only;
+        self
+    }
+
+    /// Use HTTP/2 without negotiating it first. Only use this if the server is known to support it.
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http_version = HttpVersion::Http2PriorKnowledge;
+        self
+    }
+
+    /// Set the [`RetryPolicy`] of the client.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Set the [`RateLimit`] of the client.
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = limit;
+        self
+    }
+
+    /// Start in dry-run mode, see [`Client::set_dry_run`].
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Send API requests through a custom [`Transport`] instead of the default [`ReqwestTransport`].
+    ///
+    /// The HTTP settings of this builder then only apply to [`Client::client`].
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Build the [`Client`].
+    ///
+    /// # Errors
+    /// Fails if a header, proxy URL or certificate is invalid, if a TLS backend cannot be initialized,
+    /// or the resolver cannot load the system configuration.
+    pub fn build(self) -> Result<Client, Error> {
+        let cookies = Arc::new(Jar::default());
+        let mut builder = reqwest::Client::builder()
+            .cookie_provider(cookies.clone())
+            .user_agent(self.user_agent);
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            builder =
+                builder.proxy(reqwest::Proxy::all(&proxy).map_err(|e| {
+                    Error::InvalidInput(format!("Invalid proxy \"{}\": {}", proxy, e))
+                })?);
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                Error::InvalidInput(format!("Invalid header name \"{}\": {}", name, e))
+            })?;
+            let value = HeaderValue::from_str(&value).map_err(|e| {
+                Error::InvalidInput(format!("Invalid value for header \"{}\": {}", name, e))
+            })?;
+            headers.append(name, value);
+        }
+        builder = builder.default_headers(headers);
+
+        for pem in self.root_certificates {
+            builder = builder.add_root_certificate(
+                reqwest::Certificate::from_pem(&pem)
+                    .map_err(|e| Error::InvalidInput(format!("Invalid certificate: {}", e)))?,
+            );
+        }
+
+        builder = match self.http_version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1Only => builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder.http2_prior_knowledge(),
+        };
+
+        let client = builder.build()?;
+        let transport = self
+            .transport
+            .unwrap_or_else(|| Arc::new(ReqwestTransport::new(client.clone(), cookies)));
+
+        Ok(Client {
+            client,
+            transport,
+            url: self.url,
+            session: Arc::new(RwLock::default()),
+            retry: self.retry,
+            limiter: Arc::new(RateLimiter::new(self.rate_limit)),
+            pending_login: None,
+            warning_handler: None,
+            dry_run: self.dry_run,
+            dry_run_handler: None,
+            progress: None,
+            cancel: None,
+            refresh: Arc::default(),
+        })
+    }
+}
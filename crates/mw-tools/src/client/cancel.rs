@@ -0,0 +1,57 @@
+use std::{future::Future, pin::pin, time::Duration};
+
+use futures_util::future::{select, Either};
+use tokio_util::sync::CancellationToken;
+
+use super::Client;
+use crate::Error;
+
+impl Client {
+    /// Stop all following requests and bulk operations of this client once `token` gets cancelled.
+    ///
+    /// Bulk functions check the token between items, and the client while waiting for retries or the rate limit.
+    /// Requests already sent are not aborted. To cancel a single job, set a token on a clone of the client.
+    /// Clones share the login session.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel = Some(token);
+    }
+
+    /// Get the cancellation token of this client, if any.
+    #[must_use]
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancel.as_ref()
+    }
+
+    /// Check if the cancellation token of this client was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Fail with [`Error::Cancelled`] if the cancellation token of this client was cancelled.
+    pub(crate) fn check_cancelled(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+
+    // Run `future` unless the cancellation token fires first.
+    pub(crate) async fn unless_cancelled<F: Future>(&self, future: F) -> Result<F::Output, Error> {
+        let Some(token) = &self.cancel else {
+            return Ok(future.await);
+        };
+        self.check_cancelled()?;
+
+        match select(pin!(future), pin!(token.cancelled())).await {
+            Either::Left((output, _)) => Ok(output),
+            Either::Right(_) => Err(Error::Cancelled),
+        }
+    }
+
+    pub(super) async fn sleep(&self, delay: Duration) -> Result<(), Error> {
+        self.unless_cancelled(tokio::time::sleep(delay)).await
+    }
+}
@@ -0,0 +1,122 @@
+use serde::Serialize;
+
+use super::{Client, Credentials};
+use crate::{
+    response::clientlogin::{AuthRequest, ClientLogin},
+    Error,
+};
+
+/// Result of a step of the interactive [`client_login`](Client::client_login) flow.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LoginStep {
+    /// Logged in successfully.
+    Pass,
+    /// The wiki needs more input, for example a two-factor authentication code.
+    /// Answer it via [`continue_login`](Client::continue_login).
+    Ui(LoginContinuation),
+}
+
+/// Pending step of an interactive login.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LoginContinuation {
+    /// Message to show to the user.
+    pub message: String,
+    pub requests: Vec<AuthRequest>,
+}
+
+impl Client {
+    /// Log in via `action=clientlogin`.
+    ///
+    /// Unlike [`login`](Self::login) this supports main account passwords and accounts with two-factor authentication.
+    /// If the wiki needs more information, this returns [`LoginStep::Ui`] which should be presented to the user
+    /// and answered with [`continue_login`](Self::continue_login).
+    ///
+    /// Sessions started this way can't be refreshed automatically once they expire.
+    pub async fn client_login(
+        &mut self,
+        username: &str,
+        password: &str,
+    ) -> Result<LoginStep, Error> {
+        let token = self.login_token().await?;
+        let url = self.url.clone();
+
+        self.handle_client_login(&[
+            ("action", "clientlogin"),
+            ("username", username),
+            ("password", password),
+            ("loginreturnurl", &url),
+            ("logintoken", &token),
+        ])
+        .await
+    }
+
+    /// Answer a pending [`LoginStep::Ui`].
+    ///
+    /// `answers` maps the field names of the pending [`AuthRequest`]s to the user's input.
+    /// Values of hidden fields are added automatically.
+    /// The step stays pending until the wiki answers, so it can be retried after a connection error.
+    pub async fn continue_login(&mut self, answers: &[(&str, &str)]) -> Result<LoginStep, Error> {
+        let pending = self
+            .pending_login
+            .clone()
+            .ok_or_else(|| Error::InvalidInput("no login in progress".to_string()))?;
+        let token = self.login_token().await?;
+
+        let hidden: Vec<(String, String)> = pending
+            .requests
+            .iter()
+            .flat_map(|r| &r.fields)
+            .filter(|(_, f)| f.kind == "hidden")
+            .filter_map(|(name, f)| Some((name.clone(), f.value.clone()?)))
+            .collect();
+
+        let mut parameters = vec![
+            ("action", "clientlogin"),
+            ("logincontinue", "1"),
+            ("logintoken", token.as_str()),
+        ];
+        parameters.extend(hidden.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        parameters.extend_from_slice(answers);
+
+        self.handle_client_login(&parameters).await
+    }
+
+    async fn handle_client_login(
+        &mut self,
+        parameters: &[(&str, &str)],
+    ) -> Result<LoginStep, Error> {
+        let res: ClientLogin = self.post_form(parameters).await?;
+
+        log::debug!("clientlogin request completed: {:?}", res);
+
+        let res = res.clientlogin;
+        // The wiki answered, so a previous step can't be continued anymore.
+        self.pending_login = None;
+        match res.status.as_str() {
+            "PASS" => {
+                self.request_csrf_token().await?;
+                {
+                    let mut session = self.session_mut();
+                    session.credentials = Some(Credentials::Interactive);
+                    session.site_info = None;
+                }
+
+                self.load_user_info().await?;
+                Ok(LoginStep::Pass)
+            }
+            "UI" => {
+                let continuation = LoginContinuation {
+                    message: res.message,
+                    requests: res.requests,
+                };
+                self.pending_login = Some(continuation.clone());
+                Ok(LoginStep::Ui(continuation))
+            }
+            "REDIRECT" => Err(Error::LoginFailed(
+                "login providers which redirect to a third party are not supported".to_string(),
+            )),
+            _ => Err(Error::LoginFailed(res.message)),
+        }
+    }
+}
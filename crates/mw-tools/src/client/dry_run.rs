@@ -0,0 +1,74 @@
+use std::{fmt, sync::Arc};
+
+use serde::Serialize;
+
+use super::Client;
+
+/// A change a mutating api function would have made, reported instead of sent while the client is in dry-run mode.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlannedChange {
+    /// The API action that would have been used, eg `edit`, `delete` or `move`.
+    pub action: String,
+    /// The affected page or file.
+    pub title: String,
+    /// What would happen, eg `would move to "New title"` or why it would be skipped or fail.
+    pub description: String,
+}
+
+impl fmt::Display for PlannedChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} \"{}\": {}",
+            self.action, self.title, self.description
+        )
+    }
+}
+
+// Callback set via `Client::set_dry_run_handler`. Wrapped to implement Debug.
+#[derive(Clone)]
+pub(super) struct DryRunHandler(Arc<dyn Fn(&PlannedChange) + Send + Sync>);
+
+impl fmt::Debug for DryRunHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DryRunHandler")
+    }
+}
+
+impl Client {
+    /// Enable or disable dry-run mode.
+    ///
+    /// In dry-run mode, `edit`, `nulledit`, `delete`, `rename`, `upload` and `purge` still do all reads and checks,
+    /// but report the changes they would make as [`PlannedChange`]s instead of sending them.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Check if the client is in dry-run mode.
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Call `handler` for every change skipped in dry-run mode.
+    ///
+    /// Without a handler, the changes get logged via [`log::info!`].
+    pub fn set_dry_run_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&PlannedChange) + Send + Sync + 'static,
+    {
+        self.dry_run_handler = Some(DryRunHandler(Arc::new(handler)));
+    }
+
+    pub(crate) fn report_change<S: Into<String>>(&self, action: &str, title: &str, description: S) {
+        let change = PlannedChange {
+            action: action.to_string(),
+            title: title.to_string(),
+            description: description.into(),
+        };
+        match &self.dry_run_handler {
+            Some(DryRunHandler(handler)) => handler(&change),
+            None => log::info!("[dry run] {}", change),
+        }
+    }
+}
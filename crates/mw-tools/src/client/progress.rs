@@ -0,0 +1,143 @@
+use std::{fmt, sync::Arc};
+
+use serde::Serialize;
+
+use super::Client;
+use crate::Error;
+
+/// Receives [`ProgressEvent`]s of bulk api functions like `delete`, `rename`, `purge`, `download`,
+/// `upload_multiple` and the list functions. Set it via [`Client::set_progress`].
+///
+/// Implemented for closures, so `client.set_progress(|event: &ProgressEvent| println!("{:?}", event))` works.
+pub trait Progress: Send + Sync {
+    fn on_event(&self, event: &ProgressEvent<'_>);
+}
+
+impl<F> Progress for F
+where
+    F: Fn(&ProgressEvent<'_>) + Send + Sync,
+{
+    fn on_event(&self, event: &ProgressEvent<'_>) {
+        self(event)
+    }
+}
+
+/// An event of a bulk api function.
+///
+/// `total` is `None` if the number of items isn't known upfront, eg while fetching a list.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ProgressEvent<'a> {
+    /// The function started working on its items.
+    Started {
+        operation: &'a str,
+        total: Option<usize>,
+    },
+    /// An item was processed. `done` counts every processed item so far, including failed ones.
+    ItemDone {
+        operation: &'a str,
+        title: &'a str,
+        done: usize,
+        total: Option<usize>,
+    },
+    /// Processing an item failed.
+    ItemFailed {
+        operation: &'a str,
+        title: &'a str,
+        error: &'a Error,
+        done: usize,
+        total: Option<usize>,
+    },
+    /// The function is done, either because every item was processed or because it stopped on an error.
+    Finished {
+        operation: &'a str,
+        succeeded: usize,
+        failed: usize,
+    },
+}
+
+// Observer set via `Client::set_progress`. Wrapped to implement Debug.
+#[derive(Clone)]
+pub(super) struct ProgressObserver(Arc<dyn Progress>);
+
+impl fmt::Debug for ProgressObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressObserver")
+    }
+}
+
+impl Client {
+    /// Report the progress of all following bulk operations to `observer`.
+    ///
+    /// To observe a single call, set it on a clone of the client. Clones share the login session.
+    pub fn set_progress<P: Progress + 'static>(&mut self, observer: P) {
+        self.progress = Some(ProgressObserver(Arc::new(observer)));
+    }
+
+    /// Stop reporting progress.
+    pub fn clear_progress(&mut self) {
+        self.progress = None;
+    }
+
+    pub(crate) fn track(&self, operation: &'static str, total: Option<usize>) -> Tracker<'_> {
+        let tracker = Tracker {
+            client: self,
+            operation,
+            total,
+            succeeded: 0,
+            failed: 0,
+        };
+        tracker.emit(&ProgressEvent::Started { operation, total });
+        tracker
+    }
+}
+
+/// Counts the processed items of a bulk operation and reports them to the progress observer of the client.
+///
+/// Reports `Finished` when dropped, so early returns are covered too.
+pub(crate) struct Tracker<'a> {
+    client: &'a Client,
+    operation: &'static str,
+    total: Option<usize>,
+    succeeded: usize,
+    failed: usize,
+}
+
+impl Tracker<'_> {
+    fn emit(&self, event: &ProgressEvent<'_>) {
+        if let Some(ProgressObserver(observer)) = &self.client.progress {
+            observer.on_event(event);
+        }
+    }
+
+    pub(crate) fn done(&mut self, title: &str) {
+        self.succeeded += 1;
+        self.emit(&ProgressEvent::ItemDone {
+            operation: self.operation,
+            title,
+            done: self.succeeded + self.failed,
+            total: self.total,
+        });
+    }
+
+    pub(crate) fn failed(&mut self, title: &str, error: &Error) {
+        self.failed += 1;
+        self.emit(&ProgressEvent::ItemFailed {
+            operation: self.operation,
+            title,
+            error,
+            done: self.succeeded + self.failed,
+            total: self.total,
+        });
+    }
+}
+
+impl Drop for Tracker<'_> {
+    fn drop(&mut self) {
+        self.emit(&ProgressEvent::Finished {
+            operation: self.operation,
+            succeeded: self.succeeded,
+            failed: self.failed,
+        });
+    }
+}
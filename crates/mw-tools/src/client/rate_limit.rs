@@ -0,0 +1,199 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{Semaphore, SemaphorePermit},
+    time::Instant,
+};
+
+use super::Client;
+use crate::response::userinfo::UserInfo;
+
+/// Controls how fast [`Client`](crate::Client) sends requests. Shared between clones of the client.
+///
+/// Reads are `action=query`, `action=parse` and the like, writes are everything that changes the wiki.
+/// The higher rates apply to accounts with the `apihighlimits` right, ie bots and admins.
+#[derive(Clone, Debug)]
+pub struct RateLimit {
+    /// Read requests per second. `None` disables the limit.
+    pub reads_per_second: Option<f64>,
+    /// Write requests per second. `None` disables the limit.
+    pub writes_per_second: Option<f64>,
+    /// Read requests per second for accounts with the `apihighlimits` right.
+    pub high_reads_per_second: Option<f64>,
+    /// Write requests per second for accounts with the `apihighlimits` right.
+    pub high_writes_per_second: Option<f64>,
+    /// Maximum number of requests in flight at the same time.
+    pub max_in_flight: usize,
+    /// Additionally follow the per-action `ratelimits` the wiki reports for the account, eg for `edit` or `move`.
+    pub adaptive: bool,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            reads_per_second: Some(10.0),
+            writes_per_second: Some(2.0),
+            high_reads_per_second: Some(50.0),
+            high_writes_per_second: Some(8.0),
+            max_in_flight: 4,
+            adaptive: true,
+        }
+    }
+}
+
+impl RateLimit {
+    /// No limits at all.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            reads_per_second: None,
+            writes_per_second: None,
+            high_reads_per_second: None,
+            high_writes_per_second: None,
+            max_in_flight: Semaphore::MAX_PERMITS,
+            adaptive: false,
+        }
+    }
+}
+
+// Actions which only read from the wiki.
+const READ_ACTIONS: &[&str] = &[
+    "query",
+    "parse",
+    "compare",
+    "login",
+    "clientlogin",
+    "logout",
+];
+
+/// Whether a request with `action` counts against the write budget.
+pub(crate) fn is_write(action: &str) -> bool {
+    !READ_ACTIONS.contains(&action)
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    config: RateLimit,
+    in_flight: Semaphore,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    high_limits: bool,
+    next_read: Option<Instant>,
+    next_write: Option<Instant>,
+    // action -> (interval, next slot)
+    actions: HashMap<String, (Duration, Option<Instant>)>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimit) -> Self {
+        Self {
+            in_flight: Semaphore::new(config.max_in_flight.clamp(1, Semaphore::MAX_PERMITS)),
+            config,
+            state: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn config(&self) -> &RateLimit {
+        &self.config
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Forget the limits of the previous account, eg after logging out.
+    pub(crate) fn reset(&self) {
+        let mut state = self.state();
+        state.high_limits = false;
+        state.actions.clear();
+    }
+
+    /// Adjust the limits to the rights and `ratelimits` of the logged in account.
+    pub(crate) fn update(&self, info: &UserInfo) {
+        let mut state = self.state();
+        state.high_limits = info.has_right("apihighlimits");
+        state.actions.clear();
+        if !self.config.adaptive || info.has_right("noratelimit") {
+            return;
+        }
+        for (action, limits) in &info.ratelimits {
+            // The strictest of the limits which apply to the account, eg `user` and `newbie`.
+            let interval = limits
+                .values()
+                .filter(|l| l.hits > 0)
+                .map(|l| Duration::from_secs_f64(f64::from(l.seconds) / f64::from(l.hits)))
+                .max();
+            if let Some(interval) = interval {
+                state.actions.insert(action.clone(), (interval, None));
+            }
+        }
+    }
+
+    /// Wait until the budgets allow another request with `action`, then take an in-flight slot.
+    ///
+    /// `write` selects the budget, `action` the per-action limit reported by the wiki, if any.
+    pub(crate) async fn acquire(&self, action: &str, write: bool) -> SemaphorePermit<'_> {
+        let delay = {
+            let mut state = self.state();
+            let now = Instant::now();
+
+            let interval = match (write, state.high_limits) {
+                (false, false) => self.config.reads_per_second,
+                (false, true) => self.config.high_reads_per_second,
+                (true, false) => self.config.writes_per_second,
+                (true, true) => self.config.high_writes_per_second,
+            }
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| Duration::from_secs_f64(1.0 / rate));
+
+            let mut slot = now;
+            if let Some(interval) = interval {
+                let next = if write {
+                    &mut state.next_write
+                } else {
+                    &mut state.next_read
+                };
+                slot = reserve(next, interval, now);
+            }
+            if let Some((interval, next)) = state.actions.get_mut(action) {
+                slot = slot.max(reserve(next, *interval, now));
+            }
+            slot - now
+        };
+
+        if !delay.is_zero() {
+            log::debug!(
+                "rate limit: waiting {:.2}s before \"{}\"",
+                delay.as_secs_f64(),
+                action
+            );
+            tokio::time::sleep(delay).await;
+        }
+
+        self.in_flight
+            .acquire()
+            .await
+            .expect("the semaphore is never closed")
+    }
+}
+
+impl Client {
+    // Wait for the read budget before a request outside of the API, like a file download.
+    pub(crate) async fn read_permit(&self) -> SemaphorePermit<'_> {
+        self.limiter.acquire("", false).await
+    }
+}
+
+// Take the next free slot of a budget and return it.
+fn reserve(next: &mut Option<Instant>, interval: Duration, now: Instant) -> Instant {
+    let slot = next.map_or(now, |next| next.max(now));
+    *next = Some(slot + interval);
+    slot
+}
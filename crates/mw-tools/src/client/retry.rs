@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// Controls how [`Client`](crate::Client) retries requests that failed for transient reasons.
+///
+/// Requests are retried if the API reports `maxlag`, `ratelimited` or `readonly`,
+/// if the server responds with HTTP 429 or a 5xx status, or if the request timed out.
+/// A `Retry-After` header sent by the server always takes precedence over the computed backoff.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts per request, including the first one.
+    pub max_attempts: u32,
+    /// Value for the `maxlag` parameter sent with every request. `None` disables it.
+    pub maxlag: Option<u32>,
+    /// Delay before the first retry. Doubles with every further attempt.
+    pub base_delay: Duration,
+    /// Upper bound for the computed backoff delay.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            maxlag: Some(5),
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries and doesn't send `maxlag`.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            maxlag: None,
+            ..Self::default()
+        }
+    }
+
+    /// Exponential backoff with jitter for the given (1-based) attempt.
+    ///
+    /// The result lies between half and the full computed delay.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = delay / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Parses a `Retry-After` header. Only the delay-seconds form is supported, which is what mediawiki sends.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
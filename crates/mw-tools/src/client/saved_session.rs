@@ -0,0 +1,86 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use super::{Client, Credentials};
+use crate::{response::token::Token, Error, ErrorCode};
+
+/// A login session exported via [`Client::export_session`] to be restored in a later run.
+///
+/// This contains the session cookies, so treat it like a password.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedSession {
+    /// The API URL the session belongs to.
+    pub url: String,
+    cookies: Vec<String>,
+    csrf_token: String,
+}
+
+impl Client {
+    /// Export the cookies and the csrf token of the current session.
+    ///
+    /// Returns `None` if the client isn't logged in.
+    #[must_use]
+    pub fn export_session(&self) -> Option<SavedSession> {
+        if !self.is_online() {
+            return None;
+        }
+        let cookies = self.transport.cookies(&self.url);
+
+        Some(SavedSession {
+            url: self.url.clone(),
+            cookies,
+            csrf_token: self.csrf_token(),
+        })
+    }
+
+    /// Restore a session exported via [`export_session`](Self::export_session) and check if it's still valid.
+    ///
+    /// Returns `Ok(false)` if the session expired, in which case you need to log in again.
+    /// The client's URL is set to the one of the session.
+    ///
+    /// Credentials aren't part of the session, use [`set_credentials`](Self::set_credentials)
+    /// to allow the client to log in again once the restored session expires.
+    pub async fn restore_session(&mut self, session: &SavedSession) -> Result<bool, Error> {
+        Url::parse(&session.url)
+            .map_err(|e| Error::InvalidInput(format!("Invalid session URL: {}", e)))?;
+        self.url.clone_from(&session.url);
+        for cookie in &session.cookies {
+            self.transport.add_cookie(&self.url, cookie);
+        }
+
+        let res: Result<Token, Error> = self
+            .get(&[
+                ("action", "query"),
+                ("meta", "tokens"),
+                ("type", "csrf"),
+                ("assert", "user"),
+            ])
+            .await;
+
+        let token = match res {
+            Ok(res) => res.query.tokens.csrftoken.unwrap_or_default(),
+            Err(Error::MediaWikiApi(err)) if err.code == ErrorCode::AssertUserFailed => {
+                log::debug!("saved session expired");
+                return Ok(false);
+            }
+            Err(err) => return Err(err),
+        };
+
+        {
+            let mut state = self.session_mut();
+            state.csrf_token = if token.is_empty() {
+                session.csrf_token.clone()
+            } else {
+                token
+            };
+            state.site_info = None;
+            if state.credentials.is_none() {
+                state.credentials = Some(Credentials::Interactive);
+            }
+        }
+
+        self.load_user_info().await?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,224 @@
+use std::{slice::Chunks, sync::Arc};
+
+use serde::Serialize;
+
+use super::Client;
+use crate::{response::siteinfo, Error};
+
+// Values per multi-value parameter every account may send. `apihighlimits` raises it to 500.
+const MIN_BATCH_LIMIT: usize = 50;
+
+/// Information about a wiki, fetched once via [`Client::site_info`].
+#[derive(Clone, Debug, Serialize)]
+pub struct SiteInfo {
+    pub sitename: String,
+    /// Generator string, eg `MediaWiki 1.39.3`.
+    pub generator: String,
+    /// Language code of the content language.
+    pub lang: String,
+    /// Whether the first letter of titles is case sensitive.
+    pub case_sensitive: bool,
+    pub namespaces: Vec<Namespace>,
+    pub extensions: Vec<Extension>,
+    pub magic_words: Vec<MagicWord>,
+    /// Whether the current account may use the higher limits for bots and admins.
+    pub apihighlimits: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Namespace {
+    pub id: i32,
+    /// Localized name. Empty for the main namespace.
+    pub name: String,
+    /// Canonical (english) name.
+    pub canonical: Option<String>,
+    pub aliases: Vec<String>,
+    /// Whether the first letter of titles in this namespace is case sensitive.
+    pub case_sensitive: bool,
+    /// Whether this is a content namespace.
+    pub content: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Extension {
+    pub name: String,
+    /// Kind of the extension, eg `parserhook` or `specialpage`.
+    pub kind: String,
+    pub version: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MagicWord {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub case_sensitive: bool,
+}
+
+impl From<siteinfo::Query> for SiteInfo {
+    fn from(query: siteinfo::Query) -> Self {
+        let mut namespaces: Vec<Namespace> = query
+            .namespaces
+            .into_values()
+            .map(|ns| Namespace {
+                id: ns.id,
+                name: ns.name,
+                canonical: ns.canonical,
+                aliases: query
+                    .namespacealiases
+                    .iter()
+                    .filter(|a| a.id == ns.id)
+                    .map(|a| a.alias.clone())
+                    .collect(),
+                case_sensitive: ns.case == "case-sensitive",
+                content: ns.content,
+            })
+            .collect();
+        namespaces.sort_by_key(|ns| ns.id);
+
+        Self {
+            sitename: query.general.sitename,
+            generator: query.general.generator,
+            lang: query.general.lang,
+            case_sensitive: query.general.case == "case-sensitive",
+            namespaces,
+            extensions: query
+                .extensions
+                .into_iter()
+                .map(|e| Extension {
+                    name: e.name,
+                    kind: e.kind,
+                    version: e.version,
+                })
+                .collect(),
+            magic_words: query
+                .magicwords
+                .into_iter()
+                .map(|m| MagicWord {
+                    name: m.name,
+                    aliases: m.aliases,
+                    case_sensitive: m.case_sensitive,
+                })
+                .collect(),
+            apihighlimits: query.userinfo.rights.iter().any(|r| r == "apihighlimits"),
+        }
+    }
+}
+
+impl SiteInfo {
+    /// MediaWiki version as `(major, minor)`, eg `(1, 39)`.
+    #[must_use]
+    pub fn version(&self) -> Option<(u32, u32)> {
+        let version = self.generator.strip_prefix("MediaWiki ")?;
+        let mut parts = version.split(|c: char| !c.is_ascii_digit());
+        Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+    }
+
+    /// Whether the MediaWiki version is at least `major.minor`.
+    #[must_use]
+    pub fn version_at_least(&self, major: u32, minor: u32) -> bool {
+        self.version().is_some_and(|v| v >= (major, minor))
+    }
+
+    /// Whether an extension is installed. Case insensitive.
+    #[must_use]
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions
+            .iter()
+            .any(|e| e.name.eq_ignore_ascii_case(name))
+    }
+
+    #[must_use]
+    pub fn namespace(&self, id: i32) -> Option<&Namespace> {
+        self.namespaces.iter().find(|ns| ns.id == id)
+    }
+
+    /// Find a namespace by its localized name, canonical name or an alias.
+    #[must_use]
+    pub fn namespace_by_name(&self, name: &str) -> Option<&Namespace> {
+        let name = name.trim().replace('_', " ");
+        self.namespaces.iter().find(|ns| {
+            std::iter::once(&ns.name)
+                .chain(ns.canonical.as_ref())
+                .chain(ns.aliases.iter())
+                .any(|n| !n.is_empty() && n.eq_ignore_ascii_case(&name))
+        })
+    }
+
+    /// Maximum number of values for multi-value parameters like `titles`.
+    #[must_use]
+    pub fn batch_limit(&self) -> usize {
+        if self.apihighlimits {
+            500
+        } else {
+            MIN_BATCH_LIMIT
+        }
+    }
+
+    /// Normalize a title the way MediaWiki does: underscores become spaces,
+    /// the namespace gets its localized name, and the first letter gets uppercased if the namespace isn't case sensitive.
+    #[must_use]
+    pub fn normalize_title(&self, title: &str) -> String {
+        let title = title.replace('_', " ");
+        let title = title.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+        let (namespace, rest) = match title.split_once(':') {
+            Some((prefix, rest)) => match self.namespace_by_name(prefix) {
+                Some(ns) => (Some(ns), rest.trim_start()),
+                None => (self.namespace(0), title.as_str()),
+            },
+            None => (self.namespace(0), title.as_str()),
+        };
+
+        let case_sensitive = namespace.map_or(self.case_sensitive, |ns| ns.case_sensitive);
+        let mut chars = rest.chars();
+        let rest = match chars.next() {
+            Some(first) if !case_sensitive => first.to_uppercase().chain(chars).collect(),
+            _ => rest.to_string(),
+        };
+
+        match namespace {
+            Some(ns) if ns.id != 0 => format!("{}:{}", ns.name, rest),
+            _ => rest,
+        }
+    }
+}
+
+impl Client {
+    /// Split the values of a multi-value parameter like `titles` into batches the wiki accepts, see [`SiteInfo::batch_limit`].
+    ///
+    /// Doesn't fetch the site info if `values` fit into a single batch anyway.
+    pub async fn batches<'v, T>(&self, values: &'v [T]) -> Result<Chunks<'v, T>, Error> {
+        let limit = if values.len() <= MIN_BATCH_LIMIT {
+            MIN_BATCH_LIMIT
+        } else {
+            self.site_info().await?.batch_limit()
+        };
+        Ok(values.chunks(limit))
+    }
+
+    /// Get information about the wiki and the current account.
+    ///
+    /// Fetched once and cached until the URL changes or the client logs in or out.
+    pub async fn site_info(&self) -> Result<Arc<SiteInfo>, Error> {
+        if let Some(info) = &self.session().site_info {
+            return Ok(info.clone());
+        }
+
+        let res: siteinfo::SiteInfo = self
+            .get(&[
+                ("action", "query"),
+                ("meta", "siteinfo|userinfo"),
+                (
+                    "siprop",
+                    "general|namespaces|namespacealiases|extensions|magicwords",
+                ),
+                ("uiprop", "rights"),
+            ])
+            .await?;
+
+        let info = Arc::new(SiteInfo::from(res.query));
+        self.session_mut().site_info = Some(info.clone());
+
+        Ok(info)
+    }
+}
@@ -0,0 +1,81 @@
+use futures_util::{stream, Stream};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::Client;
+use crate::Error;
+
+impl Client {
+    /// Send `action=query` requests and follow the `continue` object until all results are fetched.
+    ///
+    /// Every item of the returned stream is one batch, deserialized into `T`.
+    /// This works for any combination of lists, props and generators, because the whole `continue` object
+    /// gets sent back as-is, as recommended by the [API docs](https://www.mediawiki.org/wiki/API:Continue).
+    ///
+    /// Batches are requested lazily, so nothing gets fetched unless the stream is polled.
+    /// # Example
+    /// ```no_run
+    /// # async fn test_stream() -> Result<(), mw_tools::Error> {
+    /// # use futures_util::TryStreamExt;
+    /// # let client = mw_tools::Client::new("")?;
+    /// let mut batches = std::pin::pin!(client.query_stream::<serde_json::Value>(&[
+    ///     ("list", "allpages"),
+    ///     ("aplimit", "max"),
+    /// ]));
+    /// while let Some(batch) = batches.try_next().await? {
+    ///     println!("{}", batch["query"]["allpages"]);
+    /// }
+    /// # Ok(())}
+    /// ```
+    pub fn query_stream<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        parameters: &[(&str, &str)],
+    ) -> impl Stream<Item = Result<T, Error>> + 'a {
+        let mut base: Vec<(String, String)> = vec![("action".to_string(), "query".to_string())];
+        base.extend(
+            parameters
+                .iter()
+                .filter(|(k, _)| *k != "action")
+                .map(|(k, v)| (k.to_string(), v.to_string())),
+        );
+
+        stream::try_unfold(Some(Vec::new()), move |continuation| {
+            let base = base.clone();
+            async move {
+                let Some(continuation) = continuation else {
+                    return Ok(None);
+                };
+
+                let parameters: Vec<(&str, &str)> = base
+                    .iter()
+                    .filter(|(k, _)| !continuation.iter().any(|(c, _)| c == k))
+                    .chain(continuation.iter())
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect();
+
+                let mut res: Value = self.get(&parameters).await?;
+                let next = continuation_parameters(res.get_mut("continue").map(Value::take));
+                let batch =
+                    serde_json::from_value(res).map_err(|e| Error::ParsingFailed(e.to_string()))?;
+
+                Ok(Some((batch, next)))
+            }
+        })
+    }
+}
+
+// Turn the `continue` object of a response into parameters for the next request.
+fn continuation_parameters(value: Option<Value>) -> Option<Vec<(String, String)>> {
+    let Some(Value::Object(map)) = value else {
+        return None;
+    };
+
+    Some(
+        map.into_iter()
+            .map(|(k, v)| match v {
+                Value::String(s) => (k, s),
+                v => (k, v.to_string()),
+            })
+            .collect(),
+    )
+}
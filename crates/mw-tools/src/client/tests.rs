@@ -0,0 +1,415 @@
+use std::{sync::Arc, time::Duration};
+
+use futures_util::TryStreamExt;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use tokio_util::sync::CancellationToken;
+
+use super::{Client, MockTransport, RateLimit, RetryPolicy, TransportResponse};
+use crate::{
+    response::{clientlogin::AuthField, Ignore},
+    Error,
+};
+
+fn mock_client() -> (Client, Arc<MockTransport>) {
+    let mock = Arc::new(MockTransport::new());
+    let client = Client::builder("https://wiki.example.org/api.php")
+        .retry_policy(RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::ZERO,
+            ..RetryPolicy::default()
+        })
+        .rate_limit(RateLimit::none())
+        .transport(mock.clone())
+        .build()
+        .unwrap();
+
+    (client, mock)
+}
+
+fn user_info(rights: &[&str]) -> Value {
+    json!({ "query": { "userinfo": { "id": 1, "name": "Bot", "rights": rights } } })
+}
+
+#[tokio::test]
+async fn adds_common_parameters() {
+    let (client, mock) = mock_client();
+    mock.push_json(json!({}));
+
+    client.get::<Ignore>(&[("action", "query")]).await.unwrap();
+
+    let request = &mock.requests()[0];
+    assert_eq!(request.method, reqwest::Method::GET);
+    assert_eq!(request.parameter("format"), Some("json"));
+    assert_eq!(request.parameter("formatversion"), Some("2"));
+    assert_eq!(request.parameter("maxlag"), Some("5"));
+    assert_eq!(request.parameter("action"), Some("query"));
+}
+
+#[tokio::test]
+async fn query_stream_follows_continuation() {
+    let (client, mock) = mock_client();
+    mock.push_json(json!({
+        "continue": { "apcontinue": "B", "continue": "-||" },
+        "query": { "allpages": [{ "ns": 0, "title": "A" }] }
+    }));
+    mock.push_json(json!({
+        "batchcomplete": true,
+        "query": { "allpages": [{ "ns": 0, "title": "B" }] }
+    }));
+
+    let batches: Vec<Value> = client
+        .query_stream(&[("list", "allpages")])
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(batches.len(), 2);
+    let requests = mock.requests();
+    assert_eq!(requests[0].parameter("apcontinue"), None);
+    assert_eq!(requests[1].parameter("apcontinue"), Some("B"));
+    assert_eq!(requests[1].parameter("continue"), Some("-||"));
+    assert_eq!(requests[1].parameter("list"), Some("allpages"));
+}
+
+#[tokio::test]
+async fn list_collects_all_batches() {
+    let (client, mock) = mock_client();
+    mock.push_json(json!({
+        "continue": { "accontinue": "B", "continue": "-||" },
+        "query": { "allcategories": [{ "category": "A" }] }
+    }));
+    mock.push_json(json!({
+        "query": { "allcategories": [{ "category": "B" }, { "category": "C" }] }
+    }));
+
+    let categories = crate::api::list::allcategories(&client).await.unwrap();
+
+    assert_eq!(categories, ["A", "B", "C"]);
+    assert_eq!(mock.requests()[0].parameter("aclimit"), Some("max"));
+}
+
+#[tokio::test]
+async fn fails_on_malformed_list_items() {
+    let (client, mock) = mock_client();
+    mock.push_json(json!({
+        "query": { "allcategories": [{ "category": "A" }, { "size": 3 }] }
+    }));
+
+    assert!(matches!(
+        crate::api::list::allcategories(&client).await,
+        Err(Error::ParsingFailed(_))
+    ));
+}
+
+#[tokio::test]
+async fn retries_transient_api_errors() {
+    let (client, mock) = mock_client();
+    mock.push_api_error("maxlag", "Waiting for a database server");
+    mock.push_json(json!({ "ok": true }));
+
+    let res: Value = client.get(&[("action", "query")]).await.unwrap();
+
+    assert_eq!(res["ok"], true);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn retries_timeouts_and_server_errors_until_exhausted() {
+    let (client, mock) = mock_client();
+    mock.push_error(Error::Timeout("timed out".to_string()));
+    mock.push_response(TransportResponse {
+        status: StatusCode::SERVICE_UNAVAILABLE,
+        retry_after: Some(Duration::ZERO),
+        body: Vec::new(),
+    });
+    mock.push_error(Error::Timeout("timed out".to_string()));
+
+    let err = client
+        .get::<Ignore>(&[("action", "query")])
+        .await
+        .unwrap_err();
+
+    match err {
+        Error::RetriesExhausted { attempts, last } => {
+            assert_eq!(attempts, 3);
+            assert!(matches!(*last, Error::Timeout(_)));
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+    assert_eq!(mock.remaining(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancels_retry_sleeps() {
+    let (mut client, mock) = mock_client();
+    let token = CancellationToken::new();
+    client.set_cancellation_token(token.clone());
+    mock.push_response(TransportResponse {
+        status: StatusCode::SERVICE_UNAVAILABLE,
+        retry_after: Some(Duration::from_secs(3600)),
+        body: Vec::new(),
+    });
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        token.cancel();
+    });
+
+    let err = client
+        .get::<Ignore>(&[("action", "query")])
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Cancelled));
+    assert_eq!(mock.requests().len(), 1);
+    assert!(client
+        .get::<Ignore>(&[("action", "query")])
+        .await
+        .is_err_and(|err| matches!(err, Error::Cancelled)));
+}
+
+#[tokio::test]
+async fn does_not_retry_fatal_errors() {
+    let (client, mock) = mock_client();
+    mock.push_response(TransportResponse {
+        status: StatusCode::NOT_FOUND,
+        retry_after: None,
+        body: Vec::new(),
+    });
+    mock.push_json(json!({}));
+
+    let err = client
+        .get::<Ignore>(&[("action", "query")])
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::StatusCode(_)));
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn maps_api_and_parsing_errors() {
+    let (client, mock) = mock_client();
+    mock.push_api_error("permissiondenied", "You don't have permission.");
+    mock.push_response(TransportResponse {
+        status: StatusCode::OK,
+        retry_after: None,
+        body: b"<html>".to_vec(),
+    });
+
+    match client.get::<Ignore>(&[("action", "query")]).await {
+        Err(Error::MediaWikiApi(err)) => {
+            assert_eq!(err.code, crate::response::ErrorCode::PermissionDenied);
+            assert_eq!(err.description, "You don't have permission.");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+    assert!(matches!(
+        client.get::<Ignore>(&[("action", "query")]).await,
+        Err(Error::ParsingFailed(_))
+    ));
+}
+
+#[tokio::test]
+async fn refreshes_token_and_replays_once() {
+    let (client, mock) = mock_client();
+    mock.push_api_error("badtoken", "Invalid CSRF token.");
+    mock.push_json(json!({ "query": { "tokens": { "csrftoken": "new+\\" } } }));
+    mock.push_json(json!({ "delete": { "title": "A" } }));
+
+    client
+        .post::<Ignore>(&[("action", "delete"), ("title", "A")])
+        .await
+        .unwrap();
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].method, reqwest::Method::POST);
+    assert_eq!(requests[1].parameter("type"), Some("csrf"));
+    assert_eq!(requests[2].parameter("token"), Some("new+\\"));
+}
+
+#[tokio::test]
+async fn fails_fast_without_right() {
+    let (client, mock) = mock_client();
+    mock.push_json(user_info(&["read", "edit"]));
+
+    let err = crate::api::delete::delete(&client, &["A", "B"], None)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::MissingRight(right) if right == "delete"));
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn reports_warnings() {
+    let (mut client, mock) = mock_client();
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = received.clone();
+    client.set_warning_handler(move |w| sink.lock().unwrap().push(w.code.clone()));
+    mock.push_json(json!({
+        "warnings": [{ "code": "unrecognizedparams", "text": "Unrecognized parameter: aplimt.", "module": "main" }],
+        "query": { "allpages": [] }
+    }));
+
+    let result = crate::api::query::Query::new()
+        .list(crate::api::query::List::AllPages)
+        .fetch(&client)
+        .await
+        .unwrap();
+
+    assert_eq!(*received.lock().unwrap(), ["unrecognizedparams"]);
+    assert_eq!(result.warnings[0].module, "main");
+}
+
+#[tokio::test]
+async fn keeps_response_with_malformed_warnings() {
+    let (client, mock) = mock_client();
+    mock.push_json(json!({
+        "warnings": { "main": { "*": "legacy format" } },
+        "query": { "allpages": [{ "ns": 0, "title": "A" }] }
+    }));
+
+    let pages = crate::api::list::allpages(&client, None).await.unwrap();
+
+    assert_eq!(pages, ["A"]);
+}
+
+#[tokio::test]
+async fn preserves_all_errors() {
+    let (client, mock) = mock_client();
+    mock.push_json(json!({ "errors": [
+        { "code": "blocked", "text": "You have been blocked.", "module": "edit", "data": { "blockinfo": { "blockid": 7 } } },
+        { "code": "some-new-code", "text": "Something else.", "module": "main" }
+    ] }));
+
+    let err = match client.get::<Ignore>(&[("action", "query")]).await {
+        Err(Error::MediaWikiApi(err)) => err,
+        res => panic!("unexpected result: {:?}", res),
+    };
+
+    assert_eq!(err.code, crate::ErrorCode::Blocked);
+    assert_eq!(err.module, "edit");
+    assert_eq!(err.data.as_ref().unwrap()["blockinfo"]["blockid"], 7);
+    let codes: Vec<&str> = err.all().map(|e| e.code.as_str()).collect();
+    assert_eq!(codes, ["blocked", "some-new-code"]);
+    assert!(!err.retryable());
+}
+
+#[tokio::test]
+async fn rejects_empty_errors() {
+    let (client, mock) = mock_client();
+    mock.push_json(json!({ "errors": [] }));
+
+    assert!(matches!(
+        client.get::<Ignore>(&[("action", "query")]).await,
+        Err(Error::ParsingFailed(_))
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn spaces_writes_but_not_reads() {
+    let (mut client, mock) = mock_client();
+    client.set_rate_limit(RateLimit {
+        reads_per_second: None,
+        writes_per_second: Some(2.0),
+        ..RateLimit::default()
+    });
+    for _ in 0..6 {
+        mock.push_json(json!({}));
+    }
+
+    let start = tokio::time::Instant::now();
+    for _ in 0..3 {
+        client.get::<Ignore>(&[("action", "query")]).await.unwrap();
+    }
+    assert_eq!(start.elapsed(), Duration::ZERO);
+
+    for _ in 0..3 {
+        client.post::<Ignore>(&[("action", "purge")]).await.unwrap();
+    }
+    assert_eq!(start.elapsed(), Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn adapts_to_account_limits() {
+    let (mut client, mock) = mock_client();
+    client.set_rate_limit(RateLimit {
+        writes_per_second: Some(1.0),
+        high_writes_per_second: None,
+        ..RateLimit::default()
+    });
+    mock.push_json(json!({ "query": { "userinfo": {
+        "id": 1,
+        "name": "Bot",
+        "rights": ["edit", "apihighlimits"],
+        "ratelimits": { "edit": { "user": { "hits": 2, "seconds": 60 }, "ip": { "hits": 8, "seconds": 60 } } }
+    } } }));
+    for _ in 0..4 {
+        mock.push_json(json!({}));
+    }
+    client.load_user_info().await.unwrap();
+
+    // apihighlimits lifts the write budget, but the stricter edit limit still applies.
+    let start = tokio::time::Instant::now();
+    for _ in 0..2 {
+        client.post::<Ignore>(&[("action", "purge")]).await.unwrap();
+    }
+    assert_eq!(start.elapsed(), Duration::ZERO);
+    for _ in 0..2 {
+        client.post::<Ignore>(&[("action", "edit")]).await.unwrap();
+    }
+    assert_eq!(start.elapsed(), Duration::from_secs(30));
+}
+
+#[test]
+fn serializes_auth_field_kind() {
+    let field: AuthField = serde_json::from_value(json!({
+        "type": "password",
+        "label": "Password",
+    }))
+    .unwrap();
+    assert_eq!(field.kind, "password");
+
+    let value = serde_json::to_value(&field).unwrap();
+    assert_eq!(value["kind"], "password");
+    assert!(value.get("type").is_none());
+}
+
+#[tokio::test]
+async fn keeps_pending_login_after_connection_errors() {
+    let (mut client, mock) = mock_client();
+    let token = json!({ "query": { "tokens": { "logintoken": "+\\" } } });
+    mock.push_json(token.clone());
+    mock.push_json(json!({ "clientlogin": {
+        "status": "UI",
+        "message": "Enter a verification code.",
+        "requests": [],
+    } }));
+    client.client_login("Bot", "password").await.unwrap();
+
+    mock.push_json(token.clone());
+    for _ in 0..3 {
+        mock.push_error(Error::Timeout("timed out".to_string()));
+    }
+    assert!(matches!(
+        client.continue_login(&[("OATHToken", "123456")]).await,
+        Err(Error::RetriesExhausted { .. })
+    ));
+
+    mock.push_json(token);
+    mock.push_json(json!({ "clientlogin": {
+        "status": "FAIL",
+        "message": "Verification failed.",
+    } }));
+    assert!(matches!(
+        client.continue_login(&[("OATHToken", "123456")]).await,
+        Err(Error::LoginFailed(_))
+    ));
+    assert!(matches!(
+        client.continue_login(&[("OATHToken", "123456")]).await,
+        Err(Error::InvalidInput(_))
+    ));
+    assert_eq!(mock.remaining(), 0);
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use reqwest::{
+    cookie::{CookieStore, Jar},
+    header::RETRY_AFTER,
+    Method, StatusCode, Url,
+};
+
+use crate::Error;
+
+/// Sends requests built by [`Client`](crate::Client) to the mediawiki API.
+///
+/// [`ReqwestTransport`] is used by default. Use [`ClientBuilder::transport`](crate::ClientBuilder::transport)
+/// to swap it, eg for a [`MockTransport`] in tests.
+///
+/// Retries, error mapping and deserialization happen in the `Client`, so implementations only need to move bytes.
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    /// Send a single request. Timeouts should be reported as [`Error::Timeout`] to make them retryable.
+    fn send(&self, request: ApiRequest) -> BoxFuture<'_, Result<TransportResponse, Error>>;
+
+    /// The cookies stored for `url`, in `name=value` form.
+    fn cookies(&self, _url: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Store a cookie in `name=value` form for `url`.
+    fn add_cookie(&self, _url: &str, _cookie: &str) {}
+}
+
+/// A request to api.php.
+#[derive(Clone, Debug)]
+pub struct ApiRequest {
+    pub method: Method,
+    pub url: String,
+    /// All parameters, including `format` and friends added by the client.
+    pub parameters: Vec<(String, String)>,
+    /// OAuth 2 access token to send as a bearer token.
+    pub bearer_token: Option<String>,
+    /// File to send with a `multipart/form-data` request.
+    pub file: Option<UploadFile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct UploadFile {
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+impl ApiRequest {
+    /// Get the value of a parameter.
+    #[must_use]
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Length of the URL with all parameters in the query string, as it would be sent by a GET request.
+    #[must_use]
+    pub fn url_length(&self) -> usize {
+        match Url::parse(&self.url) {
+            Ok(mut url) => {
+                url.query_pairs_mut().extend_pairs(self.parameters.iter());
+                url.as_str().len()
+            }
+            Err(_) => self.url.len(),
+        }
+    }
+}
+
+/// The raw response to an [`ApiRequest`].
+#[derive(Clone, Debug)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    /// Delay requested by the server via the `Retry-After` header.
+    pub retry_after: Option<Duration>,
+    pub body: Vec<u8>,
+}
+
+/// The default [`Transport`], backed by a [`reqwest::Client`] with a cookie jar.
+#[derive(Debug)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    cookies: Arc<Jar>,
+}
+
+impl ReqwestTransport {
+    /// `client` needs to use `cookies` as its cookie provider for logins to work.
+    #[must_use]
+    pub fn new(client: reqwest::Client, cookies: Arc<Jar>) -> Self {
+        Self { client, cookies }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: ApiRequest) -> BoxFuture<'_, Result<TransportResponse, Error>> {
+        Box::pin(async move {
+            let mut builder = self.client.request(request.method.clone(), &request.url);
+            if let Some(token) = &request.bearer_token {
+                builder = builder.bearer_auth(token);
+            }
+            builder = match (request.file, request.method) {
+                (Some(file), _) => {
+                    let part = reqwest::multipart::Part::bytes(file.content).file_name(file.name);
+                    let mut form = reqwest::multipart::Form::new().part("file", part);
+                    for (k, v) in request.parameters {
+                        form = form.text(k, v);
+                    }
+                    builder.multipart(form)
+                }
+                (None, Method::GET) => builder.query(&request.parameters),
+                (None, _) => builder.form(&request.parameters),
+            };
+
+            let res = builder.send().await?;
+            let status = res.status();
+            let retry_after = res
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(super::retry::parse_retry_after);
+            let body = res.bytes().await?.to_vec();
+
+            Ok(TransportResponse {
+                status,
+                retry_after,
+                body,
+            })
+        })
+    }
+
+    fn cookies(&self, url: &str) -> Vec<String> {
+        let Ok(url) = Url::parse(url) else {
+            return Vec::new();
+        };
+        self.cookies
+            .cookies(&url)
+            .and_then(|header| header.to_str().ok().map(ToString::to_string))
+            .map(|header| header.split("; ").map(ToString::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    fn add_cookie(&self, url: &str, cookie: &str) {
+        if let Ok(url) = Url::parse(url) {
+            self.cookies
+                .add_cookie_str(&format!("{}; Path=/", cookie), &url);
+        }
+    }
+}
+
+/// An in-memory [`Transport`] which replays scripted responses in order and records every request.
+///
+/// Fails with [`Error::Other`] once it runs out of responses.
+/// # Example
+/// ```
+/// # async fn test_mock() -> Result<(), mw_tools::Error> {
+/// use std::sync::Arc;
+/// use mw_tools::{Client, MockTransport};
+///
+/// let mock = Arc::new(MockTransport::new());
+/// mock.push_json(serde_json::json!({ "parse": { "wikitext": "Hello" } }));
+///
+/// let client = Client::builder("https://example.org/api.php")
+///     .transport(mock.clone())
+///     .build()?;
+/// let _: serde_json::Value = client.get(&[("action", "parse"), ("page", "Main Page")]).await?;
+///
+/// assert_eq!(mock.requests()[0].parameter("page"), Some("Main Page"));
+/// # Ok(())}
+/// ```
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    responses: Mutex<VecDeque<Result<TransportResponse, Error>>>,
+    requests: Mutex<Vec<ApiRequest>>,
+    cookies: Mutex<Vec<String>>,
+}
+
+impl MockTransport {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response with status 200 and `body` serialized as json.
+    pub fn push_json(&self, body: serde_json::Value) {
+        self.push_response(TransportResponse {
+            status: StatusCode::OK,
+            retry_after: None,
+            body: body.to_string().into_bytes(),
+        });
+    }
+
+    /// Queue a mediawiki error response with status 200, like the API sends them.
+    pub fn push_api_error(&self, code: &str, text: &str) {
+        self.push_json(serde_json::json!({
+            "errors": [{ "code": code, "text": text, "module": "main" }]
+        }));
+    }
+
+    pub fn push_response(&self, response: TransportResponse) {
+        lock(&self.responses).push_back(Ok(response));
+    }
+
+    /// Queue a failure of the transport itself, eg [`Error::Timeout`].
+    pub fn push_error(&self, error: Error) {
+        lock(&self.responses).push_back(Err(error));
+    }
+
+    /// All requests sent so far, in order.
+    #[must_use]
+    pub fn requests(&self) -> Vec<ApiRequest> {
+        lock(&self.requests).clone()
+    }
+
+    /// Number of queued responses which weren't requested yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        lock(&self.responses).len()
+    }
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: ApiRequest) -> BoxFuture<'_, Result<TransportResponse, Error>> {
+        let description = format!("{:?}", request.parameters);
+        lock(&self.requests).push(request);
+        let response = lock(&self.responses).pop_front();
+        Box::pin(async move {
+            response.unwrap_or_else(|| {
+                Err(Error::Other(format!(
+                    "MockTransport has no response left for {}",
+                    description
+                )))
+            })
+        })
+    }
+
+    fn cookies(&self, _url: &str) -> Vec<String> {
+        lock(&self.cookies).clone()
+    }
+
+    fn add_cookie(&self, _url: &str, cookie: &str) {
+        lock(&self.cookies).push(cookie.to_string());
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use super::Client;
+use crate::{
+    response::userinfo::{UserInfo, UserInfoResponse},
+    Error,
+};
+
+impl Client {
+    /// Get the rights, groups and rate limits of the current account.
+    ///
+    /// Loaded automatically after logging in and cached until the URL changes or the client logs in or out.
+    pub async fn user_info(&self) -> Result<Arc<UserInfo>, Error> {
+        if let Some(info) = &self.session().user_info {
+            return Ok(info.clone());
+        }
+
+        self.load_user_info().await
+    }
+
+    /// Fail with [`Error::MissingRight`] unless the current account has all of `rights`.
+    pub async fn require_rights(&self, rights: &[&str]) -> Result<(), Error> {
+        let info = self.user_info().await?;
+
+        match rights.iter().find(|right| !info.has_right(right)) {
+            Some(right) => Err(Error::MissingRight(right.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) async fn load_user_info(&self) -> Result<Arc<UserInfo>, Error> {
+        let res: UserInfoResponse = self
+            .get(&[
+                ("action", "query"),
+                ("meta", "userinfo"),
+                ("uiprop", "rights|groups|ratelimits"),
+            ])
+            .await?;
+
+        log::debug!(
+            "logged in as {} with groups {:?}",
+            res.query.userinfo.name,
+            res.query.userinfo.groups
+        );
+
+        let info = Arc::new(res.query.userinfo);
+        self.limiter.update(&info);
+        self.session_mut().user_info = Some(info.clone());
+
+        Ok(info)
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use super::Client;
+use crate::response::Warning;
+
+// Callback set via `Client::set_warning_handler`. Wrapped to implement Debug.
+#[derive(Clone)]
+pub(super) struct WarningHandler(Arc<dyn Fn(&Warning) + Send + Sync>);
+
+impl std::fmt::Debug for WarningHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WarningHandler")
+    }
+}
+
+impl Client {
+    /// Call `handler` for every warning the API attaches to a response,
+    /// eg for deprecated or unrecognized parameters or truncated results.
+    ///
+    /// Without a handler, warnings get logged via [`log::warn!`].
+    pub fn set_warning_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&Warning) + Send + Sync + 'static,
+    {
+        self.warning_handler = Some(WarningHandler(Arc::new(handler)));
+    }
+
+    pub(super) fn report_warnings(&self, warnings: &[Warning]) {
+        for warning in warnings {
+            match &self.warning_handler {
+                Some(WarningHandler(handler)) => handler(warning),
+                None => log::warn!("{}", warning),
+            }
+        }
+    }
+}
@@ -7,6 +7,10 @@ pub enum Error {
     TokenNotFound(String),
     #[error("Login failed! API returned: \"{0}\"")]
     LoginFailed(String),
+    #[error("The account is missing the \"{0}\" right")]
+    MissingRight(String),
+    #[error("\"{}\" was changed since its content was fetched", .0.title)]
+    EditConflict(Box<crate::api::edit::EditConflict>),
 
     /// IOError
     #[error(transparent)]
@@ -19,6 +23,12 @@ pub enum Error {
     StatusCode(String),
     #[error("Error executing request: \"{0}\"")]
     RequestFailed(String),
+    #[error("Giving up after {attempts} attempts. Last error: {last}")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        last: Box<Error>,
+    },
     #[error("Error parsing body as json: \"{0}\"")]
     ParsingFailed(String),
     /// Catch-all reqwest errors
@@ -29,25 +39,46 @@ pub enum Error {
     TauriError(String), */
     #[error("Invalid Input: \"{0}\"")]
     InvalidInput(String),
+    #[error("The operation was cancelled")]
+    Cancelled,
 
     #[error("{0}")]
     Other(String),
 }
 
 impl Error {
+    /// Whether the failed request may succeed if sent again later.
+    ///
+    /// True for timeouts and for API errors like `maxlag`, see [`ErrorCode::retryable`](crate::ErrorCode::retryable).
+    /// The client already retried those according to its [`RetryPolicy`](crate::RetryPolicy),
+    /// so this is mostly useful to decide whether to re-queue a failed item.
+    #[must_use]
+    pub fn retryable(&self) -> bool {
+        match self {
+            Error::MediaWikiApi(err) => err.retryable(),
+            Error::Timeout(_) => true,
+            Error::RetriesExhausted { last, .. } => last.retryable(),
+            _ => false,
+        }
+    }
+
     #[must_use]
     pub const fn code(&self) -> &'static str {
         match self {
             Error::MediaWikiApi(_) => "MediaWikiaApi",
             Error::TokenNotFound(_) => "TokenNotFound",
             Error::LoginFailed(_) => "LoginFailed",
+            Error::MissingRight(_) => "MissingRight",
+            Error::EditConflict(_) => "EditConflict",
             Error::IoError(_) => "IoError",
             Error::Timeout(_) => "Timeout",
             Error::StatusCode(_) => "StatusCode",
             Error::RequestFailed(_) => "RequestFailed",
+            Error::RetriesExhausted { .. } => "RetriesExhausted",
             Error::ParsingFailed(_) => "ParsingFailed",
             Error::HttpClient(_) => "HttpClient",
             Error::InvalidInput(_) => "InvalidInput",
+            Error::Cancelled => "Cancelled",
             Error::Other(_) => "Other",
         }
     }
@@ -76,9 +107,15 @@ impl serde::Serialize for Error {
     {
         use serde::ser::SerializeStruct;
 
-        let mut state = serializer.serialize_struct("Error", 2)?;
+        let mut state = serializer.serialize_struct("Error", 3)?;
         state.serialize_field("code", &self.code())?;
         state.serialize_field("description", &self.to_string())?;
+        // Both versions, so the GUI can offer a merge.
+        if let Error::EditConflict(conflict) = self {
+            state.serialize_field("conflict", conflict)?;
+        } else {
+            state.skip_field("conflict")?;
+        }
         state.end()
     }
 }
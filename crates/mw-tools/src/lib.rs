@@ -1,7 +1,13 @@
 #![forbid(unsafe_code)]
 
-pub use client::Client;
+pub use client::{
+    ApiRequest, Client, ClientBuilder, Extension, LoginContinuation, LoginStep, MagicWord,
+    MockTransport, Namespace, PlannedChange, Progress, ProgressEvent, RateLimit, ReqwestTransport,
+    RetryPolicy, SavedSession, SiteInfo, Transport, TransportResponse, UploadFile,
+};
 pub use error::Error;
+pub use response::ErrorCode;
+pub use tokio_util::sync::CancellationToken;
 
 mod client;
 mod error;
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ClientLogin {
+    pub(crate) clientlogin: Response,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Response {
+    pub(crate) status: String,
+    #[serde(default)]
+    pub(crate) message: String,
+    #[serde(default)]
+    pub(crate) requests: Vec<AuthRequest>,
+}
+
+/// An authentication request the user has to answer to continue logging in,
+/// for example a two-factor authentication code or a captcha.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuthRequest {
+    /// Identifier of the request, usually the class name of the auth provider.
+    pub id: String,
+    /// Input fields, keyed by the parameter name used to answer them.
+    #[serde(default)]
+    pub fields: BTreeMap<String, AuthField>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuthField {
+    /// Kind of the field: `string`, `password`, `select`, `multiselect`, `checkbox`, `button`, `hidden` or `null`.
+    /// Sent as `type` by the API, serialized as `kind`.
+    #[serde(rename(deserialize = "type"))]
+    pub kind: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub help: String,
+    #[serde(default)]
+    pub optional: bool,
+    /// Whether the value should be hidden while typing, like a password.
+    #[serde(default)]
+    pub sensitive: bool,
+    /// Preset value. For `hidden` fields this gets sent back automatically, for `null` fields it's informational (eg a captcha URL).
+    pub value: Option<String>,
+    /// Possible values for `select` and `multiselect` fields.
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
+}
+
+impl AuthField {
+    /// Whether the user has to provide a value for this field.
+    #[must_use]
+    pub fn is_input(&self) -> bool {
+        !matches!(self.kind.as_str(), "hidden" | "null" | "button")
+    }
+}
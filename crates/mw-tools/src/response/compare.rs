@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Compare {
+    pub(crate) compare: Response,
+}
+
+/// Response of `action=compare`, re-exported as [`Comparison`](crate::api::compare::Comparison).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Response {
+    /// Set if the left side is a revision or page.
+    pub fromrevid: Option<u64>,
+    pub fromtitle: Option<String>,
+    /// Set if the right side is a revision or page.
+    pub torevid: Option<u64>,
+    pub totitle: Option<String>,
+    /// The diff as rows of an html table, like shown on the wiki.
+    #[serde(default)]
+    pub body: String,
+}
@@ -2,7 +2,6 @@ use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Delete {
-    #[allow(dead_code)]
     pub(crate) delete: Response,
 }
 
@@ -12,4 +11,5 @@ pub(crate) struct Response {
     pub(crate) title: String,
     #[allow(dead_code)]
     pub(crate) reason: String,
+    pub(crate) logid: Option<u64>,
 }
@@ -1,12 +1,27 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 pub struct Edit {
     pub edit: Response,
 }
 
-#[derive(Debug, Deserialize)]
+/// Response of a saved edit, re-exported as [`EditResult`](crate::api::edit::EditResult).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Response {
+    /// Usually `Success`, or `DryRun` if the edit was skipped in dry-run mode.
     pub result: String,
     pub title: String,
+    pub pageid: Option<u64>,
+    /// The revision before the edit, `0` if the page got created.
+    pub oldrevid: Option<u64>,
+    /// The saved revision, `None` if `nochange` is set.
+    pub newrevid: Option<u64>,
+    /// Timestamp of the saved revision, `None` if `nochange` is set.
+    pub newtimestamp: Option<String>,
+    /// Whether the page got created.
+    #[serde(default)]
+    pub new: bool,
+    /// Whether the content didn't change, so no revision got saved.
+    #[serde(default)]
+    pub nochange: bool,
 }
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+/// Error codes of the mediawiki API.
+///
+/// Only codes which are commonly handled get their own variant, everything else ends up in [`Other`](Self::Other).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum ErrorCode {
+    /// The destination of a move already exists.
+    ArticleExists,
+    AssertBotFailed,
+    /// The session expired, see [`is_session_error`](Self::is_session_error).
+    AssertUserFailed,
+    BadToken,
+    BadValue,
+    Blocked,
+    CantCreate,
+    EditConflict,
+    /// The uploaded file is identical to the current version.
+    FileExistsNoChange,
+    InvalidTitle,
+    /// The database replication lag is higher than the `maxlag` parameter.
+    MaxLag,
+    MissingParam,
+    MissingTitle,
+    NoSuchRevId,
+    NotLoggedIn,
+    PageDeleted,
+    PermissionDenied,
+    ProtectedPage,
+    ProtectedTitle,
+    RateLimited,
+    /// The wiki is in read-only mode, usually for maintenance.
+    ReadOnly,
+    SelfMove,
+    /// Any code without its own variant.
+    Other(String),
+}
+
+impl ErrorCode {
+    /// The code as sent by the API.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ArticleExists => "articleexists",
+            Self::AssertBotFailed => "assertbotfailed",
+            Self::AssertUserFailed => "assertuserfailed",
+            Self::BadToken => "badtoken",
+            Self::BadValue => "badvalue",
+            Self::Blocked => "blocked",
+            Self::CantCreate => "cantcreate",
+            Self::EditConflict => "editconflict",
+            Self::FileExistsNoChange => "fileexists-no-change",
+            Self::InvalidTitle => "invalidtitle",
+            Self::MaxLag => "maxlag",
+            Self::MissingParam => "missingparam",
+            Self::MissingTitle => "missingtitle",
+            Self::NoSuchRevId => "nosuchrevid",
+            Self::NotLoggedIn => "notloggedin",
+            Self::PageDeleted => "pagedeleted",
+            Self::PermissionDenied => "permissiondenied",
+            Self::ProtectedPage => "protectedpage",
+            Self::ProtectedTitle => "protectedtitle",
+            Self::RateLimited => "ratelimited",
+            Self::ReadOnly => "readonly",
+            Self::SelfMove => "selfmove",
+            Self::Other(code) => code,
+        }
+    }
+
+    /// Whether the request may succeed if sent again later, without changing anything.
+    #[must_use]
+    pub fn retryable(&self) -> bool {
+        matches!(self, Self::MaxLag | Self::RateLimited | Self::ReadOnly)
+    }
+
+    /// Whether the session or the csrf token expired, which logging in again fixes.
+    #[must_use]
+    pub fn is_session_error(&self) -> bool {
+        matches!(
+            self,
+            Self::BadToken | Self::AssertUserFailed | Self::NotLoggedIn
+        )
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            "articleexists" => Self::ArticleExists,
+            "assertbotfailed" => Self::AssertBotFailed,
+            "assertuserfailed" => Self::AssertUserFailed,
+            "badtoken" => Self::BadToken,
+            "badvalue" => Self::BadValue,
+            "blocked" => Self::Blocked,
+            "cantcreate" => Self::CantCreate,
+            "editconflict" => Self::EditConflict,
+            "fileexists-no-change" => Self::FileExistsNoChange,
+            "invalidtitle" => Self::InvalidTitle,
+            "maxlag" => Self::MaxLag,
+            "missingparam" => Self::MissingParam,
+            "missingtitle" => Self::MissingTitle,
+            "nosuchrevid" => Self::NoSuchRevId,
+            "notloggedin" => Self::NotLoggedIn,
+            "pagedeleted" => Self::PageDeleted,
+            "permissiondenied" => Self::PermissionDenied,
+            "protectedpage" => Self::ProtectedPage,
+            "protectedtitle" => Self::ProtectedTitle,
+            "ratelimited" => Self::RateLimited,
+            "readonly" => Self::ReadOnly,
+            "selfmove" => Self::SelfMove,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::Other(code) => code,
+            code => code.as_str().to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
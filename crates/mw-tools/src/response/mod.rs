@@ -1,40 +1,70 @@
 use serde::{de::IgnoredAny, Deserialize, Serialize};
 
+pub mod clientlogin;
+pub mod compare;
 pub(crate) mod delete;
-pub(crate) mod download;
 pub mod edit;
-pub(crate) mod list;
+mod error_code;
 pub(crate) mod login;
-pub(crate) mod parse;
+pub mod query;
 pub(crate) mod rename;
+pub(crate) mod siteinfo;
 pub(crate) mod token;
 pub(crate) mod upload;
+pub mod userinfo;
+
+pub use error_code::ErrorCode;
 
 pub(crate) type Ignore = IgnoredAny;
 
-#[derive(Debug, Deserialize, Serialize, thiserror::Error)]
+/// A warning the API attached to a response, eg for a deprecated parameter or a truncated result.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Warning {
+    pub code: String,
+    #[serde(rename = "text")]
+    pub description: String,
+    /// The module which emitted the warning, eg `main` or `query+allpages`.
+    #[serde(default)]
+    pub module: String,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "API warning from \"{}\": \"{}\". Description: \"{}\"",
+            self.module, self.code, self.description
+        )
+    }
+}
+
+/// An error returned by the mediawiki API.
+#[derive(Clone, Debug, Deserialize, Serialize, thiserror::Error)]
 #[error("API returned an error: \"{code}\". Description: \"{description}\"")]
 pub struct Error {
-    pub(crate) code: String,
+    pub code: ErrorCode,
     #[serde(rename = "text")]
-    pub(crate) description: String,
+    pub description: String,
+    /// The module which failed, eg `main` or `edit`.
+    #[serde(default)]
+    pub module: String,
+    /// Additional data some errors come with, eg the blocked user's block details.
+    #[serde(default)]
+    pub data: Option<Box<serde_json::Value>>,
+    /// The other errors of the same response, if the API reported more than one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional: Vec<Error>,
 }
 
-pub(crate) fn deserialize_string_from_number<'de, D>(deserializer: D) -> Result<String, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrNumber {
-        String(String),
-        Number(i64),
-        Float(f64),
+impl Error {
+    /// Whether the request may succeed if sent again later, see [`ErrorCode::retryable`].
+    #[must_use]
+    pub fn retryable(&self) -> bool {
+        self.code.retryable()
     }
 
-    match StringOrNumber::deserialize(deserializer)? {
-        StringOrNumber::String(s) => Ok(s),
-        StringOrNumber::Number(i) => Ok(i.to_string()),
-        StringOrNumber::Float(f) => Ok(f.to_string()),
+    /// All errors of the response, starting with this one.
+    pub fn all(&self) -> impl Iterator<Item = &Error> {
+        std::iter::once(self).chain(&self.additional)
     }
 }
@@ -0,0 +1,199 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::Warning;
+use crate::Error;
+
+/// Merged result of an `action=query` request built with [`Query`](crate::api::query::Query).
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct QueryResult {
+    /// Pages returned by props, `titles`/`pageids` or a generator.
+    pub pages: Vec<Page>,
+    /// Items of every requested list, keyed by the list name.
+    pub lists: BTreeMap<String, Vec<ListItem>>,
+    /// Titles that got normalized, eg `main page` -> `Main Page`.
+    pub normalized: Vec<TitleMapping>,
+    /// Resolved redirects, if `redirects` was requested.
+    pub redirects: Vec<TitleMapping>,
+    /// Warnings of every batch, eg about unrecognized parameters or truncated results.
+    pub warnings: Vec<Warning>,
+    /// Server time of the first batch, if `curtimestamp` was requested.
+    pub curtimestamp: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Batch {
+    pub(crate) query: BatchQuery,
+    pub(crate) warnings: Vec<Warning>,
+    pub(crate) curtimestamp: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct BatchQuery {
+    pub(crate) pages: Vec<Page>,
+    pub(crate) normalized: Vec<TitleMapping>,
+    pub(crate) redirects: Vec<TitleMapping>,
+    // Everything else are lists.
+    #[serde(flatten)]
+    pub(crate) lists: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Page {
+    pub pageid: Option<u64>,
+    #[serde(default)]
+    pub ns: i32,
+    pub title: String,
+    #[serde(default)]
+    pub missing: bool,
+    #[serde(default)]
+    pub invalid: bool,
+    #[serde(default)]
+    pub revisions: Vec<Revision>,
+    #[serde(default)]
+    pub categories: Vec<PageRef>,
+    #[serde(default)]
+    pub templates: Vec<PageRef>,
+    #[serde(default)]
+    pub links: Vec<PageRef>,
+    #[serde(default)]
+    pub imageinfo: Vec<ImageInfo>,
+    /// Fields of props without a dedicated field, eg `info`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Revision {
+    pub revid: Option<u64>,
+    pub parentid: Option<u64>,
+    pub user: Option<String>,
+    pub timestamp: Option<String>,
+    pub size: Option<u64>,
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Content slots, usually only `main`. Requires `rvslots` and `rvprop=content`.
+    #[serde(default)]
+    pub slots: HashMap<String, Slot>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Revision {
+    /// Content of the `main` slot.
+    #[must_use]
+    pub fn content(&self) -> Option<&str> {
+        self.slots.get("main")?.content.as_deref()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Slot {
+    pub content: Option<String>,
+    pub contentmodel: Option<String>,
+    pub contentformat: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ImageInfo {
+    pub url: Option<String>,
+    pub descriptionurl: Option<String>,
+    pub timestamp: Option<String>,
+    pub user: Option<String>,
+    pub size: Option<u64>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub mime: Option<String>,
+    pub sha1: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A page referenced by another one, eg a category or template.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PageRef {
+    #[serde(default)]
+    pub ns: i32,
+    pub title: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ListItem {
+    pub pageid: Option<u64>,
+    pub ns: Option<i32>,
+    // allcategories uses `category` instead of `title`.
+    #[serde(alias = "category")]
+    pub title: String,
+    /// List specific fields, eg `url` for exturlusage and allimages.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TitleMapping {
+    pub from: String,
+    pub to: String,
+}
+
+impl QueryResult {
+    /// Merge a further batch into this result.
+    ///
+    /// Props of the same page can be spread over multiple batches, so their arrays get concatenated.
+    ///
+    /// Fails if the items of a list don't match [`ListItem`].
+    pub(crate) fn merge(
+        &mut self,
+        batch: Batch,
+        index: &mut HashMap<String, usize>,
+    ) -> Result<(), Error> {
+        let query = batch.query;
+        self.warnings.extend(batch.warnings);
+        // The earliest timestamp is the safe one to detect edit conflicts with.
+        self.curtimestamp = self.curtimestamp.take().or(batch.curtimestamp);
+
+        for page in query.pages {
+            let key = page
+                .pageid
+                .map_or_else(|| page.title.clone(), |id| id.to_string());
+            match index.get(&key) {
+                Some(&i) => {
+                    let existing = &mut self.pages[i];
+                    existing.revisions.extend(page.revisions);
+                    existing.categories.extend(page.categories);
+                    existing.templates.extend(page.templates);
+                    existing.links.extend(page.links);
+                    existing.imageinfo.extend(page.imageinfo);
+                    existing.extra.extend(page.extra);
+                }
+                None => {
+                    index.insert(key, self.pages.len());
+                    self.pages.push(page);
+                }
+            }
+        }
+
+        for (name, value) in query.lists {
+            // querypage nests its items inside an object.
+            let items = match value {
+                Value::Object(mut obj) => match obj.remove("results") {
+                    Some(items) => items,
+                    // Not a list, eg `badrevids`.
+                    None => continue,
+                },
+                v => v,
+            };
+            let items = serde_json::from_value::<Vec<ListItem>>(items)
+                .map_err(|e| Error::ParsingFailed(format!("list {}: {}", name, e)))?;
+            self.lists.entry(name).or_default().extend(items);
+        }
+
+        self.normalized.extend(query.normalized);
+        self.redirects.extend(query.redirects);
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SiteInfo {
+    pub(crate) query: Query,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Query {
+    pub(crate) general: General,
+    pub(crate) namespaces: HashMap<String, Namespace>,
+    #[serde(default)]
+    pub(crate) namespacealiases: Vec<NamespaceAlias>,
+    #[serde(default)]
+    pub(crate) extensions: Vec<Extension>,
+    #[serde(default)]
+    pub(crate) magicwords: Vec<MagicWord>,
+    pub(crate) userinfo: UserInfo,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct General {
+    pub(crate) sitename: String,
+    pub(crate) generator: String,
+    pub(crate) case: String,
+    #[serde(default)]
+    pub(crate) lang: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Namespace {
+    pub(crate) id: i32,
+    pub(crate) name: String,
+    pub(crate) canonical: Option<String>,
+    pub(crate) case: String,
+    #[serde(default)]
+    pub(crate) content: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NamespaceAlias {
+    pub(crate) id: i32,
+    pub(crate) alias: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Extension {
+    pub(crate) name: String,
+    #[serde(rename = "type", default)]
+    pub(crate) kind: String,
+    pub(crate) version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MagicWord {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) aliases: Vec<String>,
+    #[serde(rename = "case-sensitive", default)]
+    pub(crate) case_sensitive: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserInfo {
+    #[serde(default)]
+    pub(crate) rights: Vec<String>,
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UserInfoResponse {
+    pub(crate) query: Query,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Query {
+    pub(crate) userinfo: UserInfo,
+}
+
+/// The account the client is logged in as, see [`Client::user_info`](crate::Client::user_info).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UserInfo {
+    /// 0 for anonymous users.
+    pub id: u64,
+    /// User name, or the IP address for anonymous users.
+    pub name: String,
+    #[serde(default)]
+    pub anon: bool,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub rights: Vec<String>,
+    /// Rate limits per action (eg `edit`) and per group of limits (eg `user` or `ip`).
+    #[serde(default)]
+    pub ratelimits: BTreeMap<String, BTreeMap<String, RateLimit>>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct RateLimit {
+    pub hits: u32,
+    pub seconds: u32,
+}
+
+impl UserInfo {
+    #[must_use]
+    pub fn has_right(&self, right: &str) -> bool {
+        self.rights.iter().any(|r| r == right)
+    }
+
+    #[must_use]
+    pub fn in_group(&self, group: &str) -> bool {
+        self.groups.iter().any(|g| g == group)
+    }
+}
@@ -0,0 +1,748 @@
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+
+use mock_wiki::{MockWiki, PASSWORD, USER};
+use mw_tools::{
+    api::{
+        self,
+        compare::{compare, diff, Change, ChangeKind, Granularity, Side},
+        edit::{CreateMode, EditRequest, Watchlist},
+        history::History,
+        query::{Prop, Query},
+        rename::Destination,
+        report::{ItemData, Outcome},
+    },
+    CancellationToken, Client, Error, ErrorCode, LoginStep, ProgressEvent,
+};
+
+async fn logged_in(wiki: &MockWiki) -> Client {
+    let mut client = Client::new(wiki.url()).unwrap();
+    client.login(USER, PASSWORD).await.unwrap();
+    client
+}
+
+#[tokio::test]
+async fn login_and_logout() {
+    let wiki = MockWiki::start().await;
+    let mut client = Client::new(wiki.url()).unwrap();
+
+    assert!(matches!(
+        client.login(USER, "wrong").await,
+        Err(Error::LoginFailed(_))
+    ));
+    client.login(USER, PASSWORD).await.unwrap();
+    assert!(client.is_online());
+    assert_eq!(client.user_info().await.unwrap().name, USER);
+
+    client.logout().await.unwrap();
+    assert!(!client.is_online());
+}
+
+#[tokio::test]
+async fn clientlogin_and_oauth() {
+    let wiki = MockWiki::start().await;
+    wiki.add_access_token("secret-token", USER);
+
+    let mut client = Client::new(wiki.url()).unwrap();
+    assert!(matches!(
+        client.client_login(USER, PASSWORD).await.unwrap(),
+        LoginStep::Pass
+    ));
+    assert!(client.is_online());
+
+    let mut client = Client::new(wiki.url()).unwrap();
+    client.login_oauth("secret-token").await.unwrap();
+    assert_eq!(client.user_info().await.unwrap().name, USER);
+}
+
+#[tokio::test]
+async fn restores_saved_session() {
+    let wiki = MockWiki::start().await;
+    let session = logged_in(&wiki).await.export_session().unwrap();
+
+    let mut client = Client::new("").unwrap();
+    assert!(client.restore_session(&session).await.unwrap());
+    assert!(client.is_online());
+}
+
+#[tokio::test]
+async fn lists_follow_continuation() {
+    let wiki = MockWiki::start().await;
+    wiki.set_max_limit(2);
+    wiki.add_page("Alpha", "[[Category:Letters]] [[Beta]] {{Box}}");
+    wiki.add_page("Beta", "[[Category:Letters]] [[File:Logo.png]]");
+    wiki.add_page("Gamma", "[[Category:Greek]] [[Beta]] {{box|x=1}}");
+    wiki.add_page("Template:Box", "box");
+    wiki.add_file("Logo.png", b"png");
+    let client = logged_in(&wiki).await;
+
+    assert_eq!(
+        api::list::allpages(&client, None).await.unwrap(),
+        ["Alpha", "Beta", "Gamma"]
+    );
+    assert_eq!(
+        api::list::allpages(&client, Some("all")).await.unwrap(),
+        ["Alpha", "Beta", "Gamma", "File:Logo.png", "Template:Box"]
+    );
+    assert_eq!(
+        api::list::allcategories(&client).await.unwrap(),
+        ["Greek", "Letters"]
+    );
+    assert_eq!(
+        api::list::allimages(&client).await.unwrap(),
+        ["File:Logo.png"]
+    );
+    assert_eq!(
+        api::list::categorymembers(&client, "Category:Letters")
+            .await
+            .unwrap(),
+        ["Alpha", "Beta"]
+    );
+    assert_eq!(
+        api::list::backlinks(&client, "Beta").await.unwrap(),
+        ["Alpha", "Gamma"]
+    );
+    assert_eq!(
+        api::list::embeddedin(&client, "Template:Box")
+            .await
+            .unwrap(),
+        ["Alpha", "Gamma"]
+    );
+    assert_eq!(
+        api::list::imageusage(&client, "File:Logo.png")
+            .await
+            .unwrap(),
+        ["Beta"]
+    );
+    assert_eq!(
+        api::list::search(&client, "greek").await.unwrap(),
+        ["Gamma"]
+    );
+}
+
+#[tokio::test]
+async fn batches_titles_by_limits() {
+    let wiki = MockWiki::start().await;
+    let titles: Vec<String> = (0..120).map(|i| format!("Page {}", i)).collect();
+    for title in &titles {
+        wiki.add_page(title, "text");
+    }
+
+    // Anonymous users may send 50 titles per request.
+    let client = Client::new(wiki.url()).unwrap();
+    let result = Query::new()
+        .prop(Prop::Revisions)
+        .titles(&titles)
+        .fetch(&client)
+        .await
+        .unwrap();
+    assert_eq!(result.pages.len(), 120);
+    // One for the site info, three for the titles.
+    assert_eq!(wiki.requests(), 4);
+
+    // 500 with apihighlimits, sent as POST once the URL gets too long.
+    let client = logged_in(&wiki).await;
+    let long: Vec<String> = titles.iter().map(|t| format!("{:0>90}", t)).collect();
+    let requests = wiki.requests();
+    let result = Query::new()
+        .prop(Prop::Revisions)
+        .titles(&long)
+        .fetch(&client)
+        .await
+        .unwrap();
+    assert_eq!(result.pages.len(), 120);
+    assert_eq!(wiki.requests(), requests + 2);
+}
+
+#[tokio::test]
+async fn detects_edit_conflicts() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("Page", "old");
+    wiki.add_page("Gone", "old");
+    let client = logged_in(&wiki).await;
+
+    let base = api::parse::get_page_content(&client, "Page").await.unwrap();
+    wiki.add_page("Page", "theirs");
+    let err = api::edit::edit(&client, "Page", "ours", None, Some(&base))
+        .await
+        .unwrap_err();
+    let Error::EditConflict(conflict) = err else {
+        panic!("unexpected error: {:?}", err);
+    };
+    assert_eq!(conflict.ours, "ours");
+    assert_eq!(conflict.base, base);
+    let theirs = conflict.theirs.unwrap();
+    assert_eq!(theirs.wikitext, "theirs");
+    assert_eq!(wiki.page("Page").as_deref(), Some("theirs"));
+
+    // Saving on top of the current version goes through.
+    api::edit::edit(&client, "Page", "ours", None, Some(&theirs))
+        .await
+        .unwrap();
+    assert_eq!(wiki.page("Page").as_deref(), Some("ours"));
+
+    let base = api::parse::get_page_content(&client, "Gone").await.unwrap();
+    api::delete::delete(&client, &["Gone"], None).await.unwrap();
+    let err = api::edit::edit(&client, "Gone", "ours", None, Some(&base))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::EditConflict(c) if c.theirs.is_none()));
+    assert!(wiki.page("Gone").is_none());
+}
+
+#[tokio::test]
+async fn fetches_pages_content() {
+    let wiki = MockWiki::start().await;
+    let revid = wiki.add_page("Page", "text");
+    wiki.add_page("Old", "#REDIRECT [[Page]]");
+    let hidden = wiki.add_page("Hidden", "secret");
+    wiki.hide_revision(hidden);
+    let client = Client::new(wiki.url()).unwrap();
+
+    let titles = ["page", "Missing", "<>", "Old", "Hidden"];
+    let fetched = api::parse::get_pages_content(&client, &titles, true)
+        .await
+        .unwrap();
+    assert_eq!(wiki.requests(), 1);
+    let requested: Vec<&str> = fetched.iter().map(|p| p.requested.as_str()).collect();
+    assert_eq!(requested, titles);
+
+    let page = fetched[0].content.as_ref().unwrap();
+    assert_eq!(
+        (page.title.as_str(), page.wikitext.as_str()),
+        ("Page", "text")
+    );
+    assert_eq!(page.revid, revid);
+    assert!(!page.starttimestamp.is_empty());
+    assert!(matches!(
+        &fetched[1].content,
+        Err(Error::MediaWikiApi(e)) if e.code == ErrorCode::MissingTitle
+    ));
+    assert!(matches!(
+        &fetched[2].content,
+        Err(Error::MediaWikiApi(e)) if e.code == ErrorCode::InvalidTitle
+    ));
+    assert_eq!(fetched[3].redirect.as_deref(), Some("Page"));
+    assert_eq!(fetched[3].content.as_ref().unwrap(), page);
+    assert!(matches!(
+        &fetched[4].content,
+        Err(Error::MediaWikiApi(e)) if e.code == ErrorCode::PermissionDenied
+    ));
+
+    // Without following redirects the redirect page itself is returned.
+    let fetched = api::parse::get_pages_content(&client, &["Old"], false)
+        .await
+        .unwrap();
+    assert_eq!(fetched[0].redirect, None);
+    assert_eq!(
+        fetched[0].content.as_ref().unwrap().wikitext,
+        "#REDIRECT [[Page]]"
+    );
+}
+
+#[tokio::test]
+async fn lists_history() {
+    let wiki = MockWiki::start().await;
+    let first = wiki.add_page("Page", "one");
+    let spam = wiki.add_revision("Page", "spam", "Vandal", "spam");
+    let fixed = wiki.add_revision("Page", "three", "Other", "fix");
+    let last = wiki.add_revision("Page", "spam again", "Vandal", "");
+    wiki.set_max_limit(3);
+    let client = Client::new(wiki.url()).unwrap();
+
+    let revisions = History::new("Page").fetch(&client).await.unwrap();
+    let revids: Vec<u64> = revisions.iter().filter_map(|r| r.revid).collect();
+    assert_eq!(revids, [last, fixed, spam, first]);
+    assert_eq!(revisions[1].parentid, Some(spam));
+    assert_eq!(revisions[1].user.as_deref(), Some("Other"));
+    assert_eq!(revisions[1].comment.as_deref(), Some("fix"));
+    assert_eq!(revisions[1].size, Some(5));
+
+    // The revision to restore when reverting Vandal.
+    let revisions = History::new("Page")
+        .exclude_user("Vandal")
+        .limit(1)
+        .fetch(&client)
+        .await
+        .unwrap();
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].revid, Some(fixed));
+
+    let revisions = History::new("Page")
+        .user("Vandal")
+        .oldest_first()
+        .fetch(&client)
+        .await
+        .unwrap();
+    let revids: Vec<u64> = revisions.iter().filter_map(|r| r.revid).collect();
+    assert_eq!(revids, [spam, last]);
+
+    let old = api::history::get_revision(&client, spam).await.unwrap();
+    assert_eq!(
+        (old.title.as_str(), old.wikitext.as_str()),
+        ("Page", "spam")
+    );
+    assert_eq!(old.revid, spam);
+    let err = api::history::get_revision(&client, 9999).await.unwrap_err();
+    assert!(matches!(err, Error::MediaWikiApi(e) if e.code == ErrorCode::NoSuchRevId));
+
+    wiki.hide_revision(spam);
+    let err = api::history::get_revision(&client, spam).await.unwrap_err();
+    assert!(matches!(err, Error::MediaWikiApi(e) if e.code == ErrorCode::PermissionDenied));
+    let contents = api::history::get_revisions(&client, &[first, spam])
+        .await
+        .unwrap();
+    assert_eq!(contents.keys().collect::<Vec<_>>(), [&first]);
+
+    let histories: Vec<_> = api::history::histories(
+        &client,
+        [History::new("Page").limit(2), History::new("Missing")],
+    )
+    .collect()
+    .await;
+    assert_eq!(histories.len(), 3);
+    let (title, revision) = histories[1].as_ref().unwrap();
+    assert_eq!((title.as_str(), revision.revid), ("Page", Some(fixed)));
+    assert!(matches!(
+        &histories[2],
+        Err(Error::MediaWikiApi(e)) if e.code == ErrorCode::MissingTitle
+    ));
+}
+
+#[tokio::test]
+async fn compares_revisions() {
+    let wiki = MockWiki::start().await;
+    let old = wiki.add_page("Page", "one\ntwo");
+    let new = wiki.add_page("Page", "one\n2");
+    let client = Client::new(wiki.url()).unwrap();
+
+    let comparison = compare(&client, &Side::Revision(old), &Side::Revision(new))
+        .await
+        .unwrap();
+    assert_eq!(
+        (comparison.fromrevid, comparison.torevid),
+        (Some(old), Some(new))
+    );
+    assert_eq!(comparison.totitle.as_deref(), Some("Page"));
+    assert!(comparison.body.contains("two") && comparison.body.contains("2"));
+
+    let comparison = compare(
+        &client,
+        &Side::Title("Page".to_string()),
+        &Side::Text("one\n2 & 3".to_string()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        (comparison.fromrevid, comparison.torevid),
+        (Some(new), None)
+    );
+    assert!(comparison.body.contains("2 &amp; 3"));
+
+    let err = compare(&client, &Side::Revision(9999), &Side::Revision(new))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::MediaWikiApi(e) if e.code == ErrorCode::NoSuchRevId));
+
+    let change = |kind, text: &str| Change {
+        kind,
+        text: text.to_string(),
+    };
+    assert_eq!(
+        diff("one\ntwo\n", "one\n2\n", Granularity::Lines),
+        [
+            change(ChangeKind::Equal, "one\n"),
+            change(ChangeKind::Delete, "two\n"),
+            change(ChangeKind::Insert, "2\n"),
+        ]
+    );
+    assert_eq!(
+        diff("a b c", "a x c", Granularity::Words),
+        [
+            change(ChangeKind::Equal, "a "),
+            change(ChangeKind::Delete, "b"),
+            change(ChangeKind::Insert, "x"),
+            change(ChangeKind::Equal, " c"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn edit_options() {
+    let wiki = MockWiki::start().await;
+    let old = wiki.add_page("Talk:Page", "Hello");
+    let client = logged_in(&wiki).await;
+
+    let result = EditRequest::new("Talk:Page")
+        .new_section("Question")
+        .text("Why?")
+        .minor(true)
+        .watchlist(Watchlist::Watch)
+        .tags(&["test"])
+        .verify_md5(true)
+        .send(&client)
+        .await
+        .unwrap();
+    assert_eq!(result.oldrevid, Some(old));
+    assert!(result.newrevid > Some(old));
+    assert!(result.newtimestamp.is_some());
+    assert!(!result.new);
+    assert_eq!(
+        wiki.page("Talk:Page").as_deref(),
+        Some("Hello\n\n== Question ==\n\nWhy?")
+    );
+
+    let result = EditRequest::new("Talk:Page")
+        .append("")
+        .send(&client)
+        .await
+        .unwrap();
+    assert!(result.nochange);
+    assert_eq!(result.newrevid, None);
+
+    let err = EditRequest::new("Talk:Page")
+        .text("new")
+        .create_mode(CreateMode::CreateOnly)
+        .send(&client)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::MediaWikiApi(e) if e.code == ErrorCode::ArticleExists));
+    let err = EditRequest::new("Missing")
+        .prepend("new")
+        .create_mode(CreateMode::NoCreate)
+        .send(&client)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::MediaWikiApi(e) if e.code == ErrorCode::MissingTitle));
+    assert!(matches!(
+        EditRequest::new("Missing").send(&client).await,
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn edit_and_read_pages() {
+    let wiki = MockWiki::start().await;
+    let existing = wiki.add_page("Existing", "old");
+    let client = logged_in(&wiki).await;
+
+    let result = api::edit::edit(&client, "New page", "content", Some("create"), None)
+        .await
+        .unwrap();
+    assert_eq!(result.result, "Success");
+    assert!(result.new);
+    assert_eq!(wiki.page("New page").as_deref(), Some("content"));
+    let page = api::parse::get_page_content(&client, "New_page")
+        .await
+        .unwrap();
+    assert_eq!(page.title, "New page");
+    assert_eq!(page.wikitext, "content");
+
+    let report = api::edit::nulledit(&client, &["Existing", "Missing"])
+        .await
+        .unwrap();
+    assert_eq!(wiki.revisions("Existing"), 1);
+    assert!(matches!(
+        &report.items[0].outcome,
+        Outcome::Success(ItemData { revid: Some(revid), .. }) if *revid == existing
+    ));
+    assert!(matches!(
+        &report.items[1].outcome,
+        Outcome::Failed(Error::MediaWikiApi(err)) if err.code == ErrorCode::MissingTitle
+    ));
+}
+
+#[tokio::test]
+async fn rename_pages() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    wiki.add_page("B", "b");
+    let client = logged_in(&wiki).await;
+
+    api::rename::rename(
+        &client,
+        vec!["A".to_string()],
+        Some(Destination::Plain(vec!["C".to_string()])),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let report = api::rename::rename(
+        &client,
+        vec!["B".to_string(), "Missing".to_string()],
+        None,
+        Some("Old "),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.to_string(), "1 succeeded, 0 skipped, 1 failed");
+    assert!(matches!(
+        &report.items[0].outcome,
+        Outcome::Success(ItemData { new_title: Some(title), .. }) if title == "Old B"
+    ));
+
+    assert_eq!(wiki.page("C").as_deref(), Some("a"));
+    assert_eq!(wiki.page("Old B").as_deref(), Some("b"));
+    assert_eq!(wiki.page("A").as_deref(), Some("#REDIRECT [[C]]"));
+
+    // Accounts without move-subpages can still move pages.
+    wiki.add_user("Mover", "moverpassword", &["read", "edit", "move"]);
+    let mut client = Client::new(wiki.url()).unwrap();
+    client.login("Mover", "moverpassword").await.unwrap();
+    let report = api::rename::rename(
+        &client,
+        vec!["C".to_string()],
+        Some(Destination::Plain(vec!["D".to_string()])),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(report.is_success());
+    assert_eq!(wiki.page("D").as_deref(), Some("a"));
+}
+
+#[tokio::test]
+async fn delete_pages() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    wiki.add_page("B", "b");
+    let client = logged_in(&wiki).await;
+
+    let report = api::delete::delete(&client, &["A", "Missing"], Some("cleanup"))
+        .await
+        .unwrap();
+
+    assert_eq!(wiki.titles(), ["B"]);
+    assert!(!report.is_success());
+    let failed: Vec<&str> = report.failed().map(|i| i.title.as_str()).collect();
+    assert_eq!(failed, ["Missing"]);
+    assert!(matches!(
+        report.items[0].outcome,
+        Outcome::Success(ItemData { logid: Some(_), .. })
+    ));
+}
+
+#[tokio::test]
+async fn checks_rights_before_deleting() {
+    let wiki = MockWiki::start().await;
+    wiki.add_user("Editor", "pass", &["read", "edit"]);
+    wiki.add_page("A", "a");
+    let mut client = Client::new(wiki.url()).unwrap();
+    client.login("Editor", "pass").await.unwrap();
+
+    assert!(matches!(
+        api::delete::delete(&client, &["A"], None).await,
+        Err(Error::MissingRight(right)) if right == "delete"
+    ));
+    assert_eq!(wiki.titles(), ["A"]);
+}
+
+#[tokio::test]
+async fn upload_files() {
+    let wiki = MockWiki::start().await;
+    let client = logged_in(&wiki).await;
+    let dir = std::env::temp_dir().join(format!("mw-tools-upload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Test_file.txt");
+    std::fs::write(&path, b"file content").unwrap();
+
+    let result = api::upload::upload(&client, &path, Some("description"))
+        .await
+        .unwrap();
+
+    assert_eq!(result, "Success");
+    assert_eq!(wiki.file("Test file.txt").unwrap(), b"file content");
+    assert_eq!(
+        wiki.page("File:Test file.txt").as_deref(),
+        Some("description")
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn purge_pages() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    wiki.add_page("B", "b");
+    let client = logged_in(&wiki).await;
+
+    api::purge::purge(&client, &["A", "B", "Missing"], false)
+        .await
+        .unwrap();
+
+    assert_eq!(wiki.purged(), ["A", "B"]);
+}
+
+#[tokio::test]
+async fn refreshes_expired_session() {
+    let wiki = MockWiki::start().await;
+    let client = logged_in(&wiki).await;
+
+    wiki.expire_sessions();
+    api::edit::edit(&client, "Page", "text", None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(wiki.page("Page").as_deref(), Some("text"));
+}
+
+#[tokio::test]
+async fn dry_run_changes_nothing() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    wiki.add_page("B", "b");
+    let mut client = logged_in(&wiki).await;
+    client.set_dry_run(true);
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let sink = changes.clone();
+    client.set_dry_run_handler(move |change| sink.lock().unwrap().push(change.clone()));
+
+    assert_eq!(
+        api::edit::edit(&client, "A", "new", None, None)
+            .await
+            .unwrap()
+            .result,
+        "DryRun"
+    );
+    api::edit::nulledit(&client, &["Missing"]).await.unwrap();
+    api::delete::delete(&client, &["B"], None).await.unwrap();
+    api::rename::rename(
+        &client,
+        vec!["A".to_string()],
+        Some(Destination::Plain(vec!["B".to_string()])),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    api::purge::purge(&client, &["A"], false).await.unwrap();
+
+    assert_eq!(wiki.titles(), ["A", "B"]);
+    assert_eq!(wiki.page("A").as_deref(), Some("a"));
+    assert!(wiki.purged().is_empty());
+    let changes: Vec<(String, String, String)> = changes
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|c| (c.action.clone(), c.title.clone(), c.description.clone()))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("edit", "A", "would change the page from 1 to 3 bytes"),
+            (
+                "edit",
+                "Missing",
+                "page doesn't exist, the null edit would fail"
+            ),
+            ("delete", "B", "would delete, reason: automated action"),
+            ("move", "A", "\"B\" already exists, the move would fail"),
+            ("purge", "A", "would purge and update links"),
+        ]
+        .map(|(a, t, d)| (a.to_string(), t.to_string(), d.to_string()))
+    );
+}
+
+#[tokio::test]
+async fn reports_progress() {
+    let wiki = MockWiki::start().await;
+    wiki.set_max_limit(1);
+    wiki.add_page("A", "a");
+    wiki.add_page("B", "b");
+    let mut client = logged_in(&wiki).await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    client.set_progress(move |event: &ProgressEvent| {
+        let event = match event {
+            ProgressEvent::Started { operation, total } => {
+                format!("{} started {:?}", operation, total)
+            }
+            ProgressEvent::ItemDone { title, done, .. } => format!("{} done {}", title, done),
+            ProgressEvent::ItemFailed { title, error, .. } => {
+                format!("{} failed {}", title, error.code())
+            }
+            ProgressEvent::Finished {
+                succeeded, failed, ..
+            } => format!("finished {}/{}", succeeded, failed),
+        };
+        sink.lock().unwrap().push(event);
+    });
+
+    api::list::allpages(&client, None).await.unwrap();
+    api::delete::delete(&client, &["A", "Missing"], None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "list started None",
+            "A done 1",
+            "B done 2",
+            "finished 2/0",
+            "delete started Some(2)",
+            "A done 1",
+            "Missing failed MediaWikiaApi",
+            "finished 1/1",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn refreshes_expired_session_once() {
+    let wiki = MockWiki::start().await;
+    let client = logged_in(&wiki).await;
+    assert_eq!(wiki.logins(), 1);
+    wiki.expire_sessions();
+
+    let (a, b) = futures_util::future::join(
+        client.post::<serde_json::Value>(&[("action", "edit"), ("title", "A"), ("text", "new")]),
+        client.post::<serde_json::Value>(&[("action", "edit"), ("title", "B"), ("text", "new")]),
+    )
+    .await;
+    a.unwrap();
+    b.unwrap();
+
+    assert_eq!(wiki.logins(), 2);
+    assert_eq!(wiki.page("A").as_deref(), Some("new"));
+    assert_eq!(wiki.page("B").as_deref(), Some("new"));
+}
+
+#[tokio::test]
+async fn cancels_between_items() {
+    let wiki = MockWiki::start().await;
+    wiki.add_page("A", "a");
+    wiki.add_page("B", "b");
+    wiki.add_page("C", "c");
+    let mut client = logged_in(&wiki).await;
+    let token = CancellationToken::new();
+    client.set_cancellation_token(token.clone());
+    let trigger = token.clone();
+    client.set_progress(move |event: &ProgressEvent| {
+        if let ProgressEvent::ItemDone { .. } = event {
+            trigger.cancel();
+        }
+    });
+
+    let report = api::delete::delete(&client, &["A", "B", "C"], None)
+        .await
+        .unwrap();
+    assert_eq!(report.succeeded().count(), 1);
+    assert_eq!(report.cancelled().count(), 2);
+    assert_eq!(report.skipped().count(), 0);
+    assert!(!report.is_success());
+    assert_eq!(
+        report.to_string(),
+        "1 succeeded, 0 skipped, 0 failed, 2 cancelled"
+    );
+    assert!(wiki.page("B").is_some());
+
+    assert!(matches!(
+        api::list::allpages(&client, None).await,
+        Err(Error::Cancelled)
+    ));
+}
@@ -0,0 +1,38 @@
+use mock_wiki::{MockWiki, PASSWORD, USER};
+use mw_tools::{api, Client};
+
+// Own test binary, because the download folder is configured via the environment of the whole process.
+#[tokio::test]
+async fn download_files() {
+    let home = std::env::temp_dir().join(format!("mw-tools-download-{}", std::process::id()));
+    let downloads = home.join("Downloads");
+    std::fs::create_dir_all(home.join(".config")).unwrap();
+    std::fs::create_dir_all(&downloads).unwrap();
+    std::fs::write(
+        home.join(".config/user-dirs.dirs"),
+        "XDG_DOWNLOAD_DIR=\"$HOME/Downloads\"\n",
+    )
+    .unwrap();
+    std::env::set_var("HOME", &home);
+    std::env::remove_var("XDG_CONFIG_HOME");
+
+    let wiki = MockWiki::start().await;
+    wiki.add_file("First.png", b"first");
+    wiki.add_file("Second.png", b"second");
+    let mut client = Client::new(wiki.url()).unwrap();
+    client.login(USER, PASSWORD).await.unwrap();
+
+    api::download::download(&client, &["File:First.png", "File:Second.png"])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read(downloads.join("First.png")).unwrap(),
+        b"first"
+    );
+    assert_eq!(
+        std::fs::read(downloads.join("Second.png")).unwrap(),
+        b"second"
+    );
+    std::fs::remove_dir_all(home).unwrap();
+}
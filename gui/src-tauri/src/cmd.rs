@@ -1,12 +1,21 @@
-use std::{collections::HashMap, sync::atomic::Ordering};
+use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::{command, Emitter};
 
-use mw_tools::{api, Error};
+use mw_tools::{
+    api,
+    api::{
+        compare::{Change, Granularity},
+        edit::EditResult,
+        parse::PageContent,
+        report::Report,
+    },
+    CancellationToken, Client, Error, LoginContinuation, LoginStep, SavedSession,
+};
 
-use crate::{CANCEL_ACTION, CLIENT};
+use crate::{CLIENT, JOBS};
 
 type Cache = parking_lot::Mutex<HashMap<String, Value>>;
 type Result<T, E = Error> = core::result::Result<T, E>;
@@ -20,6 +29,9 @@ pub(crate) struct Profile {
     username: String,
     /// Password of the account
     password: String,
+    /// OAuth 2 access token of an owner-only consumer. Used instead of username and password if set.
+    #[serde(rename = "accessToken", default)]
+    access_token: String,
     /// Wiki-URL of the account
     url: String,
     /// Whether the login credentials should be saved locally.
@@ -34,6 +46,17 @@ pub(crate) struct GetPage {
     content: String,
     /// Whether the content got edited via regex or not.
     edited: bool,
+    /// The unedited page, passed back to [`edit`] to detect edit conflicts.
+    base: PageContent,
+}
+
+/// Return value of [`login`] and [`continue_login`].
+#[derive(Debug, Serialize)]
+pub(crate) struct Login {
+    /// Index of the profile that is logging in.
+    current: usize,
+    /// Additional step required by the wiki, for example a two-factor authentication code.
+    pending: Option<LoginContinuation>,
 }
 
 /// Struct for Find&Replace operations. Each operation is stored in a new instance.
@@ -50,6 +73,30 @@ pub(crate) struct FindReplace {
     pub(crate) is_regex: bool,
 }
 
+/// A running bulk command, registered under the job id passed in by the frontend so it can be cancelled via [`cancel_job`].
+///
+/// Works on its own clone of the client, so the client lock isn't held for the whole job. Unregisters itself when dropped.
+struct Job {
+    id: String,
+    client: Client,
+}
+
+impl Job {
+    async fn start(id: String) -> Self {
+        let token = CancellationToken::new();
+        JOBS.lock().insert(id.clone(), token.clone());
+        let mut client = CLIENT.lock().await.clone();
+        client.set_cancellation_token(token);
+        Self { id, client }
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        JOBS.lock().remove(&self.id);
+    }
+}
+
 /// Get json-compatible ([`serde_json::Value`]) objects from runtime cache.
 #[command]
 pub(crate) fn cache_get(key: &str, cache: tauri::State<Cache>) -> Option<Value> {
@@ -62,59 +109,159 @@ pub(crate) fn cache_set(key: String, value: Value, cache: tauri::State<Cache>) -
     cache.lock().insert(key, value).is_some()
 }
 
-/// Command to delete pages.
+/// Command to cancel the bulk command running as `job`. Returns false if there is no such job.
+#[command]
+pub(crate) fn cancel_job(job: &str) -> bool {
+    JOBS.lock()
+        .get(job)
+        .map(CancellationToken::cancel)
+        .is_some()
+}
+
+/// Command to delete pages. Returns the result of every page.
 #[command]
-pub(crate) async fn delete(pages: Vec<&str>, reason: Option<&str>) -> Result<()> {
-    api::delete::delete(&*CLIENT.lock().await, &pages, reason).await
+pub(crate) async fn delete(job: String, pages: Vec<&str>, reason: Option<&str>) -> Result<Report> {
+    let job = Job::start(job).await;
+    api::delete::delete(&job.client, &pages, reason).await
 }
 
 /// Command to download files.
 #[command]
-pub(crate) async fn download(files: Vec<&str>) -> Result<()> {
-    api::download::download(&*CLIENT.lock().await, &files).await
+pub(crate) async fn download(job: String, files: Vec<&str>) -> Result<()> {
+    let job = Job::start(job).await;
+    api::download::download(&job.client, &files).await
 }
 
-/// Command to save edited pages.
+/// Command to save edited pages. Fails with an `EditConflict` error containing both versions
+/// if the page changed since `base` was fetched via [`get_page`].
 #[command]
-pub(crate) async fn edit(title: &str, content: &str, summary: Option<&str>) -> Result<String> {
-    api::edit::edit(&*CLIENT.lock().await, title, content, summary).await
+pub(crate) async fn edit(
+    title: &str,
+    content: &str,
+    summary: Option<&str>,
+    base: Option<PageContent>,
+) -> Result<EditResult> {
+    api::edit::edit(
+        &*CLIENT.lock().await,
+        title,
+        content,
+        summary,
+        base.as_ref(),
+    )
+    .await
 }
 
 /// Command that runs the editor in auto-save mode.
+///
+/// The content of the next batch of pages gets fetched while the current batch is edited.
+/// Pages changed by someone else in the meantime are skipped and emitted as `page-conflict` events.
 #[command]
 pub(crate) async fn auto_edit(
+    job: String,
     titles: Vec<&str>,
     patterns: Vec<FindReplace>,
     summary: Option<&str>,
     window: tauri::Window,
 ) -> Result<()> {
-    CANCEL_ACTION.store(false, Ordering::Relaxed);
+    let job = Job::start(job).await;
 
-    for t in titles {
-        if CANCEL_ACTION.load(Ordering::Relaxed) {
-            println!("cancel_edit true");
-            break;
-        }
-        let gp = get_page(t, patterns.clone()).await?;
-        if gp.edited {
-            let _ = api::edit::edit(&*CLIENT.lock().await, t, &gp.content, summary).await?;
-            window
-                .emit("page-edited", t)
-                .map_err(|_| Error::Other("Couldn't emit event to window".to_string()))?;
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-        } else {
-            window
-                .emit("page-skipped", t)
-                .map_err(|_| Error::Other("Couldn't emit event to window".to_string()))?;
+    // Fetch the content of the next batch while the current one gets edited.
+    let prefetch = |batch: Vec<String>| {
+        let client = job.client.clone();
+        tauri::async_runtime::spawn(async move {
+            api::parse::get_pages_content(&client, &batch, false).await
+        })
+    };
+    let mut batches = job
+        .client
+        .batches(&titles)
+        .await?
+        .map(|batch| batch.iter().map(ToString::to_string).collect())
+        .collect::<Vec<Vec<String>>>()
+        .into_iter();
+    let mut next = batches.next().map(prefetch);
+
+    let result: Result<()> = async {
+        'batches: while let Some(fetching) = next.take() {
+            let fetched = match fetching
+                .await
+                .map_err(|err| Error::Other(err.to_string()))?
+            {
+                Err(Error::Cancelled) => break,
+                res => res?,
+            };
+            next = batches.next().map(prefetch);
+
+            for page in fetched {
+                if job.client.is_cancelled() {
+                    break 'batches;
+                }
+                let t = page.requested.as_str();
+                // Missing and invalid titles are skipped instead of stopping the whole run.
+                let Ok(base) = page.content else {
+                    window
+                        .emit("page-skipped", t)
+                        .map_err(|_| Error::Other("Couldn't emit event to window".to_string()))?;
+                    continue;
+                };
+                let (content, edited) = apply_patterns(&base.wikitext, patterns.clone())?;
+                if edited {
+                    match api::edit::edit(&job.client, t, &content, summary, Some(&base)).await {
+                        Err(Error::Cancelled) => break 'batches,
+                        Err(Error::EditConflict(conflict)) => {
+                            window.emit("page-conflict", conflict).map_err(|_| {
+                                Error::Other("Couldn't emit event to window".to_string())
+                            })?;
+                            continue;
+                        }
+                        res => res?,
+                    };
+                    window
+                        .emit("page-edited", t)
+                        .map_err(|_| Error::Other("Couldn't emit event to window".to_string()))?;
+                } else {
+                    window
+                        .emit("page-skipped", t)
+                        .map_err(|_| Error::Other("Couldn't emit event to window".to_string()))?;
+                }
+            }
         }
+        Ok(())
     }
-    Ok(())
+    .await;
+
+    // Don't leave the prefetch of the next batch running if the run stopped early.
+    if let Some(fetching) = next {
+        fetching.abort();
+    }
+    result
 }
 
 /// Command to get page content. Runs Find&Replace operations before returning.
 #[command]
 pub(crate) async fn get_page(page: &str, patterns: Vec<FindReplace>) -> Result<GetPage> {
-    let mut s = api::parse::get_page_content(&*CLIENT.lock().await, page).await?;
+    find_replace(&*CLIENT.lock().await, page, patterns).await
+}
+
+/// Command to diff the fetched page content against the edited one, to review the changes before saving.
+#[command]
+pub(crate) fn diff(old: &str, new: &str, granularity: Granularity) -> Vec<Change> {
+    api::compare::diff(old, new, granularity)
+}
+
+async fn find_replace(client: &Client, page: &str, patterns: Vec<FindReplace>) -> Result<GetPage> {
+    let base = api::parse::get_page_content(client, page).await?;
+    let (content, edited) = apply_patterns(&base.wikitext, patterns)?;
+    Ok(GetPage {
+        content,
+        edited,
+        base,
+    })
+}
+
+/// Runs the Find&Replace operations on `content`. Returns the result and whether anything changed.
+fn apply_patterns(content: &str, patterns: Vec<FindReplace>) -> Result<(String, bool)> {
+    let mut s = content.to_string();
     let mut edited = false;
     for pat in patterns {
         if !pat.find.is_empty() {
@@ -137,15 +284,32 @@ pub(crate) async fn get_page(page: &str, patterns: Vec<FindReplace>) -> Result<G
             }
         }
     }
-    Ok(GetPage { content: s, edited })
+    Ok((s, edited))
 }
 
-/// Command to get locally saved users and the index of the last active profile.
+/// Command to get locally saved users, the index of the last active profile
+/// and whether its saved login session could be restored.
 #[command]
-pub(crate) async fn init() -> (Vec<Profile>, usize) {
-    storage::load_secure::<(Vec<Profile>, usize)>("oB9uBQDs")
+pub(crate) async fn init() -> (Vec<Profile>, usize, bool) {
+    let (profiles, current) = storage::load_secure::<(Vec<Profile>, usize)>("oB9uBQDs")
         .await
-        .unwrap_or_default()
+        .unwrap_or_default();
+
+    let mut online = false;
+    if let Ok(Some((index, session))) =
+        storage::load_secure::<Option<(usize, SavedSession)>>("session").await
+    {
+        // Only restore the session if it belongs to the last active profile.
+        if let Some(profile) = profiles.get(current).filter(|_| index == current) {
+            let mut client = CLIENT.lock().await;
+            online = client.restore_session(&session).await.unwrap_or(false);
+            if online && profile.access_token.is_empty() && profile.username.contains('@') {
+                client.set_credentials(&profile.username, &profile.password);
+            }
+        }
+    }
+
+    (profiles, current, online)
 }
 
 /// Command to get wiki-generated page lists.
@@ -174,33 +338,92 @@ pub(crate) async fn list(listtype: &str, param: Option<&str>) -> Result<Vec<Stri
 }
 
 /// Command to login.
+///
+/// Bot passwords (`User@Bot`) use action=login, everything else goes through action=clientlogin,
+/// which may return a pending step that has to be answered via [`continue_login`].
 #[command]
-pub(crate) async fn login(profiles: Vec<Profile>, current: usize) -> Result<usize> {
+pub(crate) async fn login(profiles: Vec<Profile>, current: usize) -> Result<Login> {
     let current_profile = &profiles[current];
 
     let mut client = CLIENT.lock().await;
     client.set_url(&current_profile.url);
 
-    client
-        .login(&current_profile.username, &current_profile.password)
-        .await?;
+    let step = if !current_profile.access_token.is_empty() {
+        client.login_oauth(&current_profile.access_token).await?;
+        LoginStep::Pass
+    } else if current_profile.username.contains('@') {
+        client
+            .login(&current_profile.username, &current_profile.password)
+            .await?;
+        LoginStep::Pass
+    } else {
+        client
+            .client_login(&current_profile.username, &current_profile.password)
+            .await?
+    };
+    drop(client);
 
-    update_profile_store(profiles, current)
-        .await
-        .and(Ok(current))
+    finish_login(step, profiles, current).await
+}
+
+/// Command to answer a pending login step returned by [`login`].
+#[command]
+pub(crate) async fn continue_login(
+    profiles: Vec<Profile>,
+    current: usize,
+    answers: HashMap<String, String>,
+) -> Result<Login> {
+    let answers: Vec<(&str, &str)> = answers
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let step = CLIENT.lock().await.continue_login(&answers).await?;
+
+    finish_login(step, profiles, current).await
+}
+
+/// Store the profiles once the login went through.
+async fn finish_login(step: LoginStep, profiles: Vec<Profile>, current: usize) -> Result<Login> {
+    match step {
+        LoginStep::Pass => {
+            // The session is as sensitive as the password, so only keep it if the user wants the password saved.
+            let session = CLIENT
+                .lock()
+                .await
+                .export_session()
+                .filter(|_| profiles[current].save_password)
+                .map(|session| (current, session));
+            storage::save_secure("session", session)
+                .await
+                .map_err(|err| Error::Other(err.to_string()))?;
+            update_profile_store(profiles, current).await?;
+            Ok(Login {
+                current,
+                pending: None,
+            })
+        }
+        LoginStep::Ui(continuation) => Ok(Login {
+            current,
+            pending: Some(continuation),
+        }),
+    }
 }
 
 /// Command to logout.
 #[command]
 pub(crate) async fn logout() -> Result<()> {
+    storage::save_secure("session", None::<(usize, SavedSession)>)
+        .await
+        .map_err(|err| Error::Other(err.to_string()))?;
     CLIENT.lock().await.logout().await
 }
 
-/// Command to move pages.
+/// Command to move pages. Returns the result of every page.
 #[command]
-pub(crate) async fn rename(from: Vec<String>, to: Vec<String>) -> Result<()> {
+pub(crate) async fn rename(job: String, from: Vec<String>, to: Vec<String>) -> Result<Report> {
+    let job = Job::start(job).await;
     api::rename::rename(
-        &*CLIENT.lock().await,
+        &job.client,
         from,
         Some(api::rename::Destination::Plain(to)),
         None,
@@ -209,23 +432,36 @@ pub(crate) async fn rename(from: Vec<String>, to: Vec<String>) -> Result<()> {
     .await
 }
 
-/// Command to purge or nulledit pages.
+/// Command to purge or nulledit pages. Only null edits return the result of every page.
 #[command]
-pub(crate) async fn purge(is_nulledit: bool, pages: Vec<&str>) -> Result<()> {
-    let client = CLIENT.lock().await;
+pub(crate) async fn purge(
+    job: String,
+    is_nulledit: bool,
+    pages: Vec<&str>,
+) -> Result<Option<Report>> {
+    let job = Job::start(job).await;
     if is_nulledit {
-        api::edit::nulledit(&client, &pages).await
+        api::edit::nulledit(&job.client, &pages).await.map(Some)
     } else {
-        api::purge::purge(&client, &pages, true).await
+        api::purge::purge(&job.client, &pages, true)
+            .await
+            .map(|()| None)
     }
 }
 
+/// Command to enable or disable dry-run mode. Skipped changes are emitted as `dry-run` events.
+#[command]
+pub(crate) async fn set_dry_run(enabled: bool) {
+    CLIENT.lock().await.set_dry_run(enabled);
+}
+
 /// Command to update locally saved users.
 #[command]
 pub(crate) async fn update_profile_store(mut profiles: Vec<Profile>, current: usize) -> Result<()> {
     for p in &mut profiles {
         if !p.save_password {
             p.password = "".to_string();
+            p.access_token = "".to_string();
         }
     }
 
@@ -234,16 +470,24 @@ pub(crate) async fn update_profile_store(mut profiles: Vec<Profile>, current: us
         .map_err(|err| Error::Other(err.to_string()))
 }
 
-/// Command to upload files.
+/// Command to upload files. Stops inbetween files if the job gets cancelled.
 #[command]
-pub(crate) async fn upload(text: &str, files: Vec<&str>, window: tauri::Window) -> Result<()> {
-    CANCEL_ACTION.store(false, Ordering::Relaxed);
+pub(crate) async fn upload(
+    job: String,
+    text: &str,
+    files: Vec<&str>,
+    window: tauri::Window,
+) -> Result<()> {
+    let job = Job::start(job).await;
     let mut file_iter = files.iter();
-    while !CANCEL_ACTION.load(Ordering::Relaxed) {
+    while !job.client.is_cancelled() {
         if let Some(file) = file_iter.next() {
             // Check if path resolves to a file. Skip upload otherwise.
             if std::fs::metadata(file)?.is_file() {
-                api::upload::upload(&*CLIENT.lock().await, file, Some(text)).await?;
+                match api::upload::upload(&job.client, file, Some(text)).await {
+                    Err(Error::Cancelled) => break,
+                    res => res?,
+                };
             }
             // Emit uploaded event no matter if it's a file or a folder, to remove it from the frontend.
             window
@@ -1,17 +1,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::{
-    collections::HashMap,
-    sync::atomic::{AtomicBool, Ordering},
-};
+use std::collections::HashMap;
 
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use serde_json::Value;
-use tauri::Listener;
+use tauri::Emitter;
 use tokio::sync::Mutex as AsyncMutex;
 
-use mw_tools::Client;
+use mw_tools::{CancellationToken, Client, ProgressEvent};
 
 mod cmd;
 
@@ -19,11 +16,9 @@ mod cmd;
 // There is nothing we can do if init fails, so let's panic in the disco.
 static CLIENT: Lazy<AsyncMutex<Client>> = Lazy::new(|| AsyncMutex::new(Client::new("").unwrap()));
 
-/// Global boolean to cancel automatic actions.
-/// Currently this includes:
-/// - editing in auto-save mode.
-/// - file uploads (stopping inbetween).
-static CANCEL_ACTION: AtomicBool = AtomicBool::new(false);
+/// Cancellation tokens of the running bulk commands, by the job id the frontend passed in.
+/// Cancelling one job via `cmd::cancel_job` doesn't affect the others.
+static JOBS: Lazy<Mutex<HashMap<String, CancellationToken>>> = Lazy::new(Default::default);
 
 fn main() {
     pretty_env_logger::init();
@@ -34,10 +29,6 @@ fn main() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .on_page_load(|window, _| {
-            // Listener to cancel actions, see above.
-            window.listen("cancel-action", move |_| {
-                CANCEL_ACTION.store(true, Ordering::Relaxed);
-            });
             // add OS as global window wariable, because Windows is Windows i guess.
             // Used on Windows for:
             // TODO: Re-check this now that a few years have passed
@@ -45,12 +36,34 @@ fn main() {
             //  2. <input type="text"> and <select> elements need padding-bottom: 1px; for proper alignment.
             let _ = window.eval(&format!("window.OS='{}'", std::env::consts::OS));
         })
+        .setup(|app| {
+            let mut client = CLIENT
+                .try_lock()
+                .expect("nothing else uses the client during setup");
+            // Forward API warnings, changes skipped in dry-run mode and the progress of bulk operations to the frontend.
+            let handle = app.handle().clone();
+            client.set_warning_handler(move |warning| {
+                let _ = handle.emit("api-warning", warning);
+            });
+            let handle = app.handle().clone();
+            client.set_dry_run_handler(move |change| {
+                let _ = handle.emit("dry-run", change);
+            });
+            let handle = app.handle().clone();
+            client.set_progress(move |event: &ProgressEvent| {
+                let _ = handle.emit("progress", event);
+            });
+            Ok(())
+        })
         // Using a HashMap for the application cache.
         .manage(Mutex::new(HashMap::<String, Value>::new()))
         .invoke_handler(tauri::generate_handler![
             cmd::cache_get,
             cmd::cache_set,
+            cmd::cancel_job,
+            cmd::continue_login,
             cmd::delete,
+            cmd::diff,
             cmd::download,
             cmd::edit,
             cmd::auto_edit,
@@ -61,6 +74,7 @@ fn main() {
             cmd::logout,
             cmd::rename,
             cmd::purge,
+            cmd::set_dry_run,
             cmd::update_profile_store,
             cmd::upload
         ])
@@ -1,11 +1,13 @@
 import { useEffect, useRef, useState } from 'react';
 import { HashRouter as Router, Route, Routes } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { Account, Delete, Download, Edit, List, Move, Purge, Upload } from './pages';
 import { Header } from './components';
 import { getCache, setCache } from '@/helpers/invoke';
 import cls from './App.module.css';
-import type { Profile } from './helpers/types';
+import { dryRunToast, warningToast } from '@/helpers/toast';
+import type { ApiWarning, PlannedChange, Profile, ProgressEvent } from './helpers/types';
 
 const App = () => {
     // useRef to make useEffect skip the change from useState
@@ -16,6 +18,7 @@ const App = () => {
             profile: '',
             username: '',
             password: '',
+            accessToken: '',
             url: '',
             savePassword: false,
             isOnline: false,
@@ -24,6 +27,8 @@ const App = () => {
     const [currentProfile, setCurrentProfile] = useState(0);
     const [navDisabled, setNavDisabled] = useState(false);
     const [oldProfilesLen, setOldProfilesLen] = useState(1);
+    const [isDryRun, setIsDryRun] = useState(false);
+    const [progress, setProgress] = useState('');
 
     // Init user state from cache or default
     // This exists to handle reloads
@@ -35,8 +40,10 @@ const App = () => {
                 if (cache) {
                     setProfiles(cache);
                 } else {
-                    const init: [Profile[], number] = await invoke('init');
+                    const init: [Profile[], number, boolean] = await invoke('init');
                     if (init[0].some((p) => p.profile !== '' && p.url !== '')) {
+                        // The saved session of the last active profile got restored.
+                        if (init[2]) init[0][init[1] || 0].isOnline = true;
                         setProfiles(init[0]);
                         setCurrentProfile(init[1] || 0);
                     }
@@ -46,6 +53,47 @@ const App = () => {
         }
     }, []);
 
+    // Show warnings the API attached to any response.
+    useEffect(() => {
+        const unlisten = listen<ApiWarning>('api-warning', ({ payload }) => warningToast(payload));
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, []);
+
+    // Show changes that were skipped in dry-run mode.
+    useEffect(() => {
+        const unlisten = listen<PlannedChange>('dry-run', ({ payload }) => dryRunToast(payload));
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, []);
+
+    // Show the progress of bulk operations next to the spinner in the header.
+    useEffect(() => {
+        const unlisten = listen<ProgressEvent>('progress', ({ payload }) => {
+            switch (payload.type) {
+                case 'started':
+                    setProgress(`${payload.operation} 0/${payload.total ?? '?'}`);
+                    break;
+                case 'itemDone':
+                case 'itemFailed':
+                    setProgress(`${payload.operation} ${payload.done}/${payload.total ?? '?'}`);
+                    break;
+                case 'finished':
+                    setProgress('');
+                    break;
+            }
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, []);
+
+    const toggleDryRun = (enabled: boolean) => {
+        invoke('set_dry_run', { enabled }).then(() => setIsDryRun(enabled));
+    };
+
     // Update cache on every user object change
     // This exists to handle reloads
     useEffect(() => {
@@ -66,7 +114,13 @@ const App = () => {
     return (
         <Router>
             <div className={cls.container}>
-                <Header isDisabled={navDisabled} isOnline={profiles[currentProfile].isOnline} />
+                <Header
+                    isDisabled={navDisabled}
+                    isOnline={profiles[currentProfile].isOnline}
+                    isDryRun={isDryRun}
+                    setDryRun={toggleDryRun}
+                    progress={progress}
+                />
                 <div className={cls.center}>
                     <Routes>
                         <Route
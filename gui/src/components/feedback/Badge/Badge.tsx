@@ -2,7 +2,7 @@ import cls from './Badge.module.css';
 
 type Props = {
     children: React.ReactNode;
-    type?: 'success' | 'error';
+    type?: 'success' | 'warning' | 'error';
 };
 
 const Badge = ({ children, type }: Props) => {
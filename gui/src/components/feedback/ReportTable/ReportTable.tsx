@@ -0,0 +1,71 @@
+import cls from './ReportTable.module.css';
+import Badge from '../Badge/Badge';
+import type { Outcome, Report } from '@/helpers/types';
+
+type Props = {
+    report: Report;
+};
+
+const badge = (outcome: Outcome) => {
+    switch (outcome.status) {
+        case 'success':
+            return <Badge type="success">Done</Badge>;
+        case 'skipped':
+            return <Badge>Skipped</Badge>;
+        case 'dryRun':
+            return <Badge type="warning">Dry run</Badge>;
+        case 'failed':
+            return <Badge type="error">Failed</Badge>;
+        case 'cancelled':
+            return <Badge type="warning">Cancelled</Badge>;
+    }
+};
+
+const details = (outcome: Outcome) => {
+    switch (outcome.status) {
+        case 'success': {
+            const { newTitle, revid, logid } = outcome.details;
+            return [
+                newTitle ? `now "${newTitle}"` : '',
+                revid ? `revision ${revid}` : '',
+                logid ? `log entry ${logid}` : '',
+            ]
+                .filter(Boolean)
+                .join(', ');
+        }
+        case 'failed':
+            return outcome.details.description;
+        case 'cancelled':
+            return '';
+        default:
+            return outcome.details;
+    }
+};
+
+/** Shows the result of every title of a bulk operation. */
+const ReportTable = ({ report }: Props) => {
+    return (
+        <div className={cls.wrapper}>
+            <table className={cls.table}>
+                <thead>
+                    <tr>
+                        <th>Title</th>
+                        <th>Result</th>
+                        <th>Details</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {report.items.map((item, i) => (
+                        <tr key={'report' + i}>
+                            <td>{item.title}</td>
+                            <td>{badge(item.outcome)}</td>
+                            <td>{details(item.outcome)}</td>
+                        </tr>
+                    ))}
+                </tbody>
+            </table>
+        </div>
+    );
+};
+
+export default ReportTable;
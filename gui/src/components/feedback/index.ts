@@ -1,5 +1,6 @@
 import Badge from './Badge/Badge';
+import ReportTable from './ReportTable/ReportTable';
 import Spinner from './Spinner/Spinner';
 import { toast } from './Toast/ToastManager';
 
-export { Badge, Spinner, toast };
+export { Badge, ReportTable, Spinner, toast };
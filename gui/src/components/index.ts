@@ -1,4 +1,4 @@
-import { Badge, Spinner, toast } from './feedback';
+import { Badge, ReportTable, Spinner, toast } from './feedback';
 import { Button, Checkbox, Input, Label, Select, Textarea } from './forms';
 import { Divider, Header, Modal } from './layout';
 
@@ -11,6 +11,7 @@ export {
     Input,
     Label,
     Modal,
+    ReportTable,
     Select,
     Spinner,
     Textarea,
@@ -1,7 +1,7 @@
 import { Link, useLocation } from 'react-router-dom';
 import cls from './Header.module.css';
 import Menu from './Menu';
-import { Badge, Divider, Spinner } from '@/components';
+import { Badge, Checkbox, Divider, Spinner } from '@/components';
 import { routes } from '@/helpers/consts';
 import { Fragment } from 'react';
 
@@ -14,6 +14,9 @@ type MenuProps = {
 type HeaderProps = {
     isDisabled: boolean;
     isOnline: boolean;
+    isDryRun: boolean;
+    setDryRun: (enabled: boolean) => void;
+    progress: string;
 };
 
 const HeaderItem = ({ children, isDisabled = false, to = '/' }: MenuProps) => {
@@ -31,7 +34,7 @@ const HeaderItem = ({ children, isDisabled = false, to = '/' }: MenuProps) => {
     );
 };
 
-const Header = ({ isDisabled, isOnline }: HeaderProps) => {
+const Header = ({ isDisabled, isOnline, isDryRun, setDryRun, progress }: HeaderProps) => {
     return (
         <>
             <nav className={cls.nav}>
@@ -47,8 +50,20 @@ const Header = ({ isDisabled, isOnline }: HeaderProps) => {
                 </div>
                 <Menu />
                 <div className={cls.spacer}></div>
+                <Checkbox
+                    className={cls.dryrun}
+                    id="dry-run"
+                    isChecked={isDryRun}
+                    isDisabled={isDisabled}
+                    onChange={(e) => setDryRun(e.target.checked)}
+                >
+                    Dry run
+                </Checkbox>
                 {isDisabled ? (
-                    <Spinner />
+                    <>
+                        {progress && <span className={cls.progress}>{progress}</span>}
+                        <Spinner />
+                    </>
                 ) : (
                     <Badge type={isOnline ? 'success' : 'error'}>
                         {isOnline ? 'Online' : 'Offline'}
@@ -1,4 +1,5 @@
 import { Badge, toast } from '@/components';
+import type { ApiWarning, EditConflict, PlannedChange, Report } from './types';
 
 const errorToast = (error: { code: string; description: string }): void => {
     // TODO: log description to status bar or something
@@ -22,4 +23,48 @@ const successToast = (message?: string, description?: string): void => {
     );
 };
 
-export { errorToast, successToast };
+const warningToast = (warning: ApiWarning): void => {
+    console.log('warning', warning.module, warning.code, warning.text);
+    toast.show(
+        <>
+            <Badge type="warning">WARNING</Badge>
+            {`${warning.module}: ${warning.text}`}
+        </>,
+    );
+};
+
+const dryRunToast = (change: PlannedChange): void => {
+    console.log('dry run', change.action, change.title, change.description);
+    toast.show(
+        <>
+            <Badge type="warning">DRY RUN</Badge>
+            {`${change.action} ${change.title}: ${change.description}`}
+        </>,
+    );
+};
+
+const conflictToast = (conflict: EditConflict): void => {
+    console.log('edit conflict', conflict.title);
+    toast.show(
+        <>
+            <Badge type="error">CONFLICT</Badge>
+            {`${conflict.title} was changed in the meantime and got skipped`}
+        </>,
+    );
+};
+
+const reportToast = (action: string, report: Report): void => {
+    const failed = report.items.filter((i) => i.outcome.status === 'failed').length;
+    if (failed === 0) {
+        successToast(`${action} successful`);
+    } else {
+        toast.show(
+            <>
+                <Badge type="error">ERROR</Badge>
+                {`${action}: ${failed} of ${report.items.length} pages failed`}
+            </>,
+        );
+    }
+};
+
+export { conflictToast, dryRunToast, errorToast, reportToast, successToast, warningToast };
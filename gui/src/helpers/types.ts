@@ -8,9 +8,118 @@ type Profile = {
     profile: string;
     username: string;
     password: string;
+    accessToken: string;
     url: string;
     savePassword: boolean;
     isOnline: boolean;
 };
 
-export type { FocusableElement, Profile };
+type ApiWarning = {
+    code: string;
+    text: string;
+    module: string;
+};
+
+type PlannedChange = {
+    action: string;
+    title: string;
+    description: string;
+};
+
+type PageContent = {
+    title: string;
+    wikitext: string;
+    revid: number;
+    timestamp: string;
+    starttimestamp: string;
+};
+
+type Change = {
+    kind: 'equal' | 'insert' | 'delete';
+    text: string;
+};
+
+type EditResult = {
+    result: string;
+    title: string;
+    pageid: number | null;
+    oldrevid: number | null;
+    newrevid: number | null;
+    newtimestamp: string | null;
+    new: boolean;
+    nochange: boolean;
+};
+
+type EditConflict = {
+    title: string;
+    ours: string;
+    base: PageContent;
+    theirs: PageContent | null;
+};
+
+type ItemData = {
+    revid: number | null;
+    logid: number | null;
+    newTitle: string | null;
+};
+
+type Outcome =
+    | { status: 'success'; details: ItemData }
+    | { status: 'skipped'; details: string }
+    | { status: 'dryRun'; details: string }
+    | { status: 'failed'; details: { code: string; description: string } }
+    | { status: 'cancelled' };
+
+type Report = {
+    items: { title: string; outcome: Outcome }[];
+};
+
+type ProgressEvent =
+    | { type: 'started'; operation: string; total: number | null }
+    | { type: 'itemDone'; operation: string; title: string; done: number; total: number | null }
+    | {
+          type: 'itemFailed';
+          operation: string;
+          title: string;
+          error: { code: string; description: string };
+          done: number;
+          total: number | null;
+      }
+    | { type: 'finished'; operation: string; succeeded: number; failed: number };
+
+type AuthField = {
+    kind: string;
+    label: string;
+    help: string;
+    optional: boolean;
+    sensitive: boolean;
+    value: string | null;
+    options: Record<string, string>;
+};
+
+type LoginContinuation = {
+    message: string;
+    requests: { id: string; fields: Record<string, AuthField> }[];
+};
+
+type LoginResult = {
+    current: number;
+    pending: LoginContinuation | null;
+};
+
+export type {
+    ApiWarning,
+    AuthField,
+    Change,
+    EditConflict,
+    EditResult,
+    FocusableElement,
+    LoginContinuation,
+    LoginResult,
+    Outcome,
+    PageContent,
+    PlannedChange,
+    Profile,
+    ProgressEvent,
+    Report,
+};
@@ -6,7 +6,8 @@ import { Button, Checkbox, Divider, Input, Label, Select } from '@/components';
 import { errorToast } from '@/helpers/toast';
 import cls from './Account.module.css';
 import { CloseIcon, PlusIcon } from '@/components/icons';
-import type { Profile } from '@/helpers/types';
+import type { LoginContinuation, LoginResult, Profile } from '@/helpers/types';
+import LoginStepModal from './LoginStepModal';
 
 type Props = {
     profiles: Profile[];
@@ -27,18 +28,25 @@ const Account = ({
     const [urlInvalid, seturlInvalid] = useState(false);
     const [usernameInvalid, setUsernameInvalid] = useState(false);
     const [passwordInvalid, setPasswordInvalid] = useState(false);
+    const [pendingLogin, setPendingLogin] = useState<LoginContinuation | null>(null);
 
-    const login = () => {
+    const handleLogin = (promise: Promise<LoginResult>) => {
         setLoggingin(true);
-        invoke<number>('login', { profiles, current: currentProfile })
-            .then((res) => {
+        promise
+            .then(({ current, pending }) => {
+                if (pending) {
+                    setPendingLogin(pending);
+                    return;
+                }
+                setPendingLogin(null);
                 setProfiles((old) => {
                     const curr = [...old];
-                    curr[res].isOnline = true;
+                    curr[current].isOnline = true;
                     return curr;
                 });
             })
             .catch((err) => {
+                setPendingLogin(null);
                 setProfiles((old) => {
                     const curr = [...old];
                     curr.map((p) => (p.isOnline = false));
@@ -49,6 +57,18 @@ const Account = ({
             .finally(() => setLoggingin(false));
     };
 
+    const login = () =>
+        handleLogin(invoke<LoginResult>('login', { profiles, current: currentProfile }));
+
+    const continueLogin = (answers: Record<string, string>) =>
+        handleLogin(
+            invoke<LoginResult>('continue_login', {
+                profiles,
+                current: currentProfile,
+                answers,
+            }),
+        );
+
     const logout = () => {
         setLoggingin(true);
         invoke<never>('logout').finally(() => {
@@ -69,6 +89,7 @@ const Account = ({
                     profile: 'Profile ' + (old.length + 1),
                     username: '',
                     password: '',
+                    accessToken: '',
                     url: '',
                     savePassword: old[currentProfile].savePassword || false,
                     isOnline: false,
@@ -109,12 +130,21 @@ const Account = ({
         } else {
             seturlInvalid(false);
         }
-        if (!curr.username.includes('@')) {
+        // An OAuth access token replaces username and password.
+        const usesToken = !!curr.accessToken;
+        // Bot passwords (User@Bot) have a fixed format, main account passwords don't.
+        const isBotPassword = curr.username.includes('@');
+        if (!usesToken && curr.username.trim() === '') {
             setUsernameInvalid(true);
         } else {
             setUsernameInvalid(false);
         }
-        if (/\W/.test(curr.password) || curr.password.length <= 16) {
+        if (
+            !usesToken &&
+            (isBotPassword
+                ? /\W/.test(curr.password) || curr.password.length <= 16
+                : curr.password === '')
+        ) {
             setPasswordInvalid(true);
         } else {
             setPasswordInvalid(false);
@@ -199,7 +229,7 @@ const Account = ({
             <Divider />
             <div className="w100">
                 <Label htmlFor="username" isRequired>
-                    Bot Loginname
+                    Loginname
                 </Label>
                 <Input
                     id="username"
@@ -208,13 +238,13 @@ const Account = ({
                     isDisabled={logginin || profiles[currentProfile].isOnline}
                     isRequired
                     isInvalid={usernameInvalid}
-                    placeholder="Generated via Special:BotPasswords"
+                    placeholder="Account name or bot name generated via Special:BotPasswords"
                 />
             </div>
             <Divider />
             <div className="w100">
                 <Label htmlFor="password" isRequired>
-                    Bot Password
+                    Password
                 </Label>
                 <Input
                     id="password"
@@ -227,6 +257,18 @@ const Account = ({
                     placeholder="Generated via Special:BotPasswords"
                 />
             </div>
+            <Divider />
+            <div className="w100">
+                <Label htmlFor="accessToken">OAuth Access Token</Label>
+                <Input
+                    id="accessToken"
+                    value={profiles[currentProfile].accessToken ?? ''}
+                    onChange={handleInput}
+                    isDisabled={logginin || profiles[currentProfile].isOnline}
+                    isPassword
+                    placeholder="Owner-only consumer, replaces Loginname and Password"
+                />
+            </div>
             <div className={cls.buttons}>
                 <Checkbox
                     id="save-password"
@@ -246,6 +288,11 @@ const Account = ({
                     {profiles[currentProfile].isOnline ? 'Log out' : 'Log in'}
                 </Button>
             </div>
+            <LoginStepModal
+                continuation={pendingLogin}
+                onSubmit={continueLogin}
+                onClose={() => setPendingLogin(null)}
+            />
         </main>
     );
 };
@@ -0,0 +1,70 @@
+import { useEffect, useState } from 'react';
+
+import { Button, Input, Label, Modal } from '@/components';
+import type { LoginContinuation } from '@/helpers/types';
+import cls from './LoginStepModal.module.css';
+
+type Props = {
+    continuation: LoginContinuation | null;
+    onSubmit: (answers: Record<string, string>) => void;
+    onClose: () => void;
+};
+
+// Fields the user can't or doesn't need to fill in.
+const isInput = (kind: string) => !['hidden', 'null', 'button'].includes(kind);
+
+const LoginStepModal = ({ continuation, onSubmit, onClose }: Props) => {
+    const [answers, setAnswers] = useState<Record<string, string>>({});
+
+    useEffect(() => setAnswers({}), [continuation]);
+
+    const fields = (continuation?.requests ?? []).flatMap((r) => Object.entries(r.fields));
+
+    return (
+        <Modal
+            isOpen={!!continuation}
+            onClose={onClose}
+            header="Additional login step"
+            body={
+                <div className={cls.container}>
+                    <p>{continuation?.message}</p>
+                    {fields.map(([name, field]) =>
+                        isInput(field.kind) ? (
+                            <div key={name} className={cls.field}>
+                                <Label htmlFor={'login-' + name} isRequired={!field.optional}>
+                                    {field.label}
+                                </Label>
+                                <Input
+                                    id={'login-' + name}
+                                    value={answers[name] ?? ''}
+                                    isPassword={field.sensitive || field.kind === 'password'}
+                                    placeholder={field.help}
+                                    onChange={(event) =>
+                                        setAnswers((old) => ({
+                                            ...old,
+                                            [name]: event.target.value,
+                                        }))
+                                    }
+                                />
+                            </div>
+                        ) : field.kind === 'null' && field.value ? (
+                            <p key={name}>
+                                {field.label}: {field.value}
+                            </p>
+                        ) : null,
+                    )}
+                </div>
+            }
+            footer={
+                <>
+                    <Button className={cls.mr} colorScheme="blue" onClick={() => onSubmit(answers)}>
+                        Continue
+                    </Button>
+                    <Button onClick={onClose}>Cancel</Button>
+                </>
+            }
+        />
+    );
+};
+
+export default LoginStepModal;
@@ -1,8 +1,9 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Button, Input, Label, Textarea } from '@/components';
+import { Button, Input, Label, ReportTable, Textarea } from '@/components';
 import { getCache, setCache } from '@/helpers/invoke';
-import { errorToast, successToast } from '@/helpers/toast';
+import { errorToast, reportToast } from '@/helpers/toast';
+import type { Report } from '@/helpers/types';
 import cls from './Delete.module.css';
 
 type Props = {
@@ -14,14 +15,19 @@ const Delete = ({ isOnline, setNavDisabled }: Props) => {
     const [areaValue, setAreaValue] = useState('');
     const [isLoading, setIsLoading] = useState(false);
     const [reason, setReason] = useState('');
+    const [report, setReport] = useState<Report | null>(null);
 
     const deletePages = () => {
         setIsLoading(true);
-        invoke('delete', {
+        invoke<Report>('delete', {
+            job: 'delete',
             pages: areaValue.split(/\r?\n/),
             reason,
         })
-            .then(() => successToast('Delete successful'))
+            .then((report) => {
+                setReport(report);
+                reportToast('Delete', report);
+            })
             .catch(errorToast)
             .finally(() => setIsLoading(false));
     };
@@ -56,6 +62,7 @@ const Delete = ({ isOnline, setNavDisabled }: Props) => {
                 onBlur={() => setCache('delete-pages', areaValue)}
                 placeholder="Write exact page names here. Separated by newline."
             ></Textarea>
+            {report && <ReportTable report={report} />}
             <div>
                 <Button
                     isLoading={isLoading}
@@ -17,6 +17,7 @@ const Download = ({ isOnline, setNavDisabled }: Props) => {
     const downloadFiles = () => {
         setIsLoading(true);
         invoke('download', {
+            job: 'download',
             files: areaValue.split(/\r?\n/),
         })
             .then(() => successToast('Download successful', 'Check your download folder.'))
@@ -0,0 +1,76 @@
+import { useRef } from 'react';
+
+import { Button, Label, Modal, Textarea } from '@/components';
+import type { EditConflict } from '@/helpers/types';
+import cls from './ConflictModal.module.css';
+
+type Props = {
+    conflict: EditConflict | null;
+    onClose: () => void;
+    // Continue with the current version of the wiki.
+    onUseTheirs: () => void;
+    // Continue with the own text, saving it over the current version.
+    onKeepOurs: () => void;
+};
+
+const ConflictModal = ({ conflict, onClose, onUseTheirs, onKeepOurs }: Props) => {
+    const initialRef = useRef<HTMLButtonElement>(null);
+
+    return (
+        <Modal
+            onClose={onClose}
+            isOpen={conflict !== null}
+            initialFocusRef={initialRef}
+            header={`Edit conflict on ${conflict?.title}`}
+            body={
+                <div className={cls.container}>
+                    <div className={cls.version}>
+                        <Label htmlFor="conflict-ours">Your text</Label>
+                        <Textarea
+                            id="conflict-ours"
+                            className={cls.area}
+                            readOnly
+                            value={conflict?.ours}
+                        />
+                    </div>
+                    <div className={cls.version}>
+                        <Label htmlFor="conflict-theirs">Current version</Label>
+                        <Textarea
+                            id="conflict-theirs"
+                            className={cls.area}
+                            readOnly
+                            value={conflict?.theirs?.wikitext ?? ''}
+                            placeholder="The page got deleted in the meantime."
+                        />
+                    </div>
+                </div>
+            }
+            footer={
+                <>
+                    <Button
+                        className={cls.mr}
+                        colorScheme="blue"
+                        onClick={onUseTheirs}
+                        isDisabled={!conflict?.theirs}
+                        title="Edit the current version instead"
+                    >
+                        Use current version
+                    </Button>
+                    <Button
+                        className={cls.mr}
+                        colorScheme="red"
+                        onClick={onKeepOurs}
+                        title="Saving again overwrites the current version"
+                    >
+                        Keep my text
+                    </Button>
+                    <Button onClick={onClose} ref={initialRef}>
+                        Cancel
+                    </Button>
+                </>
+            }
+        />
+    );
+};
+
+export default ConflictModal;
@@ -0,0 +1,50 @@
+import { useRef } from 'react';
+
+import { Button, Modal } from '@/components';
+import type { Change } from '@/helpers/types';
+import cls from './DiffModal.module.css';
+
+type Props = {
+    title: string;
+    // The changes to review, null while the modal is closed.
+    changes: Change[] | null;
+    onClose: () => void;
+    onSave: () => void;
+};
+
+const DiffModal = ({ title, changes, onClose, onSave }: Props) => {
+    const initialRef = useRef<HTMLButtonElement>(null);
+    const unchanged = changes?.every((change) => change.kind === 'equal');
+
+    return (
+        <Modal
+            onClose={onClose}
+            isOpen={changes !== null}
+            initialFocusRef={initialRef}
+            header={`Changes to ${title}`}
+            body={
+                <div className={cls.diff}>
+                    {unchanged
+                        ? 'Nothing changed.'
+                        : changes?.map((change, i) => (
+                              <span key={i} className={cls[change.kind]}>
+                                  {change.text}
+                              </span>
+                          ))}
+                </div>
+            }
+            footer={
+                <>
+                    <Button className={cls.mr} colorScheme="blue" onClick={onSave}>
+                        Save
+                    </Button>
+                    <Button onClick={onClose} ref={initialRef}>
+                        Back to editing
+                    </Button>
+                </>
+            }
+        />
+    );
+};
+
+export default DiffModal;
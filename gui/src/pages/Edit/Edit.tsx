@@ -1,11 +1,14 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import ConflictModal from './ConflictModal';
+import DiffModal from './DiffModal';
 import FindReplaceModal from './FindReplaceModal';
-import { listen, emit } from '@tauri-apps/api/event';
+import { listen } from '@tauri-apps/api/event';
 import { Button, Checkbox, Input, Label, Textarea } from '@/components';
 import { getCache, setCache } from '@/helpers/invoke';
-import { errorToast, successToast } from '@/helpers/toast';
+import { conflictToast, errorToast, successToast } from '@/helpers/toast';
 import { removeFirst } from '@/helpers/array';
+import type { Change, EditConflict, EditResult, PageContent } from '@/helpers/types';
 import cls from './Edit.module.css';
 
 type Pattern = {
@@ -26,6 +29,11 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
     const [pageList, setPageList] = useState('');
     const [pageContent, setPageContent] = useState('');
     const [currentPage, setCurrentPage] = useState('');
+    // The fetched version of the current page, sent back on save to detect edit conflicts.
+    const [base, setBase] = useState<PageContent | null>(null);
+    const [conflict, setConflict] = useState<EditConflict | null>(null);
+    // Changes between the fetched and the edited content, shown before saving.
+    const [changes, setChanges] = useState<Change[] | null>(null);
     const [editSummary, setEditSummary] = useState('');
     const [patterns, setPatterns] = useState<Pattern[]>([
         { find: '', replace: '', isRegex: false },
@@ -41,6 +49,7 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
         if (isAuto) {
             setPageContent('');
             invoke('auto_edit', {
+                job: 'auto-edit',
                 titles: pageList,
                 patterns,
                 summary: editSummary,
@@ -54,7 +63,7 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
 
     const stop = () => {
         if (isAuto) {
-            emit('cancel-action');
+            invoke('cancel_job', { job: 'auto-edit' });
         }
         setPageList((state) => currentPage + '\n' + state);
         setPageContent('');
@@ -72,12 +81,13 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
             setIsRunning(false);
             setIsLoading(false);
         } else {
-            invoke<{ content: string; edited: boolean }>('get_page', {
+            invoke<{ content: string; edited: boolean; base: PageContent }>('get_page', {
                 page: curr,
                 patterns: patterns,
             })
-                .then(({ content }) => {
+                .then(({ content, base }) => {
                     setPageContent(content);
+                    setBase(base);
                 })
                 .catch((err) => {
                     stop();
@@ -87,23 +97,41 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
         }
     };
 
+    const review = () => {
+        invoke<Change[]>('diff', {
+            old: base?.wikitext ?? '',
+            new: pageContent,
+            granularity: 'words',
+        })
+            .then(setChanges)
+            .catch(errorToast);
+    };
+
     const save = () => {
         setIsLoading(true);
-        invoke<string>('edit', {
+        invoke<EditResult>('edit', {
             title: currentPage,
             content: pageContent
                 .replace(/[\u007F-\u009F\u200B]/g, '')
                 .replace(/…/g, '...')
                 .trim(),
             summary: editSummary || null,
+            base,
         })
             .then((res) => {
-                successToast('Edit successful', res);
+                successToast(
+                    res.nochange ? 'Nothing changed' : 'Edit successful',
+                    `${res.title}: ${res.result}`,
+                );
                 getNextPage();
             })
             .catch((err) => {
                 setIsLoading(false);
-                errorToast(err);
+                if (err.code === 'EditConflict') {
+                    setConflict(err.conflict);
+                } else {
+                    errorToast(err);
+                }
             });
     };
 
@@ -119,6 +147,10 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
         const unlistenSkipped = listen('page-skipped', ({ payload }: { payload: string }) => {
             setPageList((old) => removeFirst(old.split(/\r?\n/), payload).join('\n'));
         });
+        // Conflicting pages stay in the list, to be edited manually.
+        const unlistenConflict = listen('page-conflict', ({ payload }: { payload: EditConflict }) =>
+            conflictToast(payload),
+        );
 
         const init = async () => {
             const list = await getCache<string>('edit-pagelist');
@@ -137,6 +169,7 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
         return () => {
             unlistenEdited.then((f) => f());
             unlistenSkipped.then((f) => f());
+            unlistenConflict.then((f) => f());
         };
     }, []);
 
@@ -236,6 +269,14 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
                                 >
                                     Skip
                                 </Button>
+                                <Button
+                                    isDisabled={!isRunning || !currentPage || isAuto}
+                                    isLoading={isLoading}
+                                    onClick={review}
+                                    title="Review the changes before saving"
+                                >
+                                    Show changes
+                                </Button>
                                 <Button
                                     isDisabled={!isRunning || !currentPage}
                                     isLoading={isLoading}
@@ -255,6 +296,30 @@ const Edit = ({ isOnline, setNavDisabled }: Props) => {
                 patterns={patterns}
                 setPatterns={setPatterns}
             />
+
+            <DiffModal
+                title={currentPage}
+                changes={changes}
+                onClose={() => setChanges(null)}
+                onSave={() => {
+                    setChanges(null);
+                    save();
+                }}
+            />
+
+            <ConflictModal
+                conflict={conflict}
+                onClose={() => setConflict(null)}
+                onUseTheirs={() => {
+                    setPageContent(conflict?.theirs?.wikitext ?? '');
+                    setBase(conflict?.theirs ?? null);
+                    setConflict(null);
+                }}
+                onKeepOurs={() => {
+                    setBase(conflict?.theirs ?? null);
+                    setConflict(null);
+                }}
+            />
         </>
     );
 };
@@ -1,8 +1,9 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { getCache, setCache } from '@/helpers/invoke';
-import { errorToast, successToast } from '@/helpers/toast';
-import { Button, Textarea } from '@/components';
+import { errorToast, reportToast } from '@/helpers/toast';
+import { Button, ReportTable, Textarea } from '@/components';
+import type { Report } from '@/helpers/types';
 import cls from './Move.module.css';
 
 type Props = {
@@ -14,14 +15,19 @@ const Move = ({ isOnline, setNavDisabled }: Props) => {
     const [isLoading, setIsLoading] = useState(false);
     const [areaFrom, setAreaFrom] = useState('');
     const [areaTo, setAreaTo] = useState('');
+    const [report, setReport] = useState<Report | null>(null);
 
     const movePages = () => {
         setIsLoading(true);
-        invoke('rename', {
+        invoke<Report>('rename', {
+            job: 'move',
             from: areaFrom.split(/\r?\n/),
             to: areaTo.split(/\r?\n/),
         })
-            .then(() => successToast('Successfully moved pages'))
+            .then((report) => {
+                setReport(report);
+                reportToast('Move', report);
+            })
             .catch(errorToast)
             .finally(() => setIsLoading(false));
     };
@@ -57,6 +63,7 @@ const Move = ({ isOnline, setNavDisabled }: Props) => {
                     placeholder="Write exact names of destinations. Separated by newline."
                 />
             </div>
+            {report && <ReportTable report={report} />}
             <div>
                 <Button
                     isLoading={isLoading}
@@ -1,8 +1,9 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { getCache, setCache } from '@/helpers/invoke';
-import { errorToast, successToast } from '@/helpers/toast';
-import { Button, Textarea } from '@/components';
+import { errorToast, reportToast, successToast } from '@/helpers/toast';
+import { Button, ReportTable, Textarea } from '@/components';
+import type { Report } from '@/helpers/types';
 import cls from './Purge.module.css';
 
 enum Action {
@@ -19,16 +20,26 @@ type Props = {
 const Purge = ({ isOnline, setNavDisabled }: Props) => {
     const [status, setStatus] = useState(Action.None);
     const [areaValue, setAreaValue] = useState('');
+    const [report, setReport] = useState<Report | null>(null);
 
     const purgePages = (isNulledit: boolean) => {
         setNavDisabled(true);
         setStatus(isNulledit ? Action.Null : Action.Purge);
 
-        invoke('purge', {
+        invoke<Report | null>('purge', {
+            job: 'purge',
             pages: areaValue.split(/\r?\n/),
             isNulledit,
         })
-            .then(() => successToast((isNulledit ? 'Nulledit' : 'Purge') + ' successful'))
+            .then((report) => {
+                // Only null edits report the result of every page.
+                setReport(report);
+                if (report) {
+                    reportToast('Nulledit', report);
+                } else {
+                    successToast('Purge successful');
+                }
+            })
             .catch(errorToast)
             .finally(() => {
                 setNavDisabled(false);
@@ -52,6 +63,7 @@ const Purge = ({ isOnline, setNavDisabled }: Props) => {
                 onBlur={() => setCache('purge-cache', areaValue)}
                 placeholder="Write exact page names here. Separated by newline."
             />
+            {report && <ReportTable report={report} />}
             <div className={cls.buttons}>
                 <Button
                     isLoading={status === Action.Purge}
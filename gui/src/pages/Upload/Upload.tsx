@@ -2,7 +2,7 @@ import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import FileList from './FileList';
-import { emit, listen } from '@tauri-apps/api/event';
+import { listen } from '@tauri-apps/api/event';
 import { getCache, setCache } from '@/helpers/invoke';
 import { errorToast, successToast } from '@/helpers/toast';
 import { Button, Input, Label } from '@/components';
@@ -44,6 +44,7 @@ const Upload = ({ isOnline, setNavDisabled }: Props) => {
     const startUpload = () => {
         setStatus(Action.Upload);
         invoke('upload', {
+            job: 'upload',
             text: uploadtext,
             files,
         })
@@ -124,7 +125,9 @@ const Upload = ({ isOnline, setNavDisabled }: Props) => {
                         isDisabled={status === Action.Wait || !isOnline || !files[0]}
                         onClick={() => {
                             if (status === Action.Upload) {
-                                emit('cancel-action').finally(() => setStatus(Action.Wait));
+                                invoke('cancel_job', { job: 'upload' }).finally(() =>
+                                    setStatus(Action.Wait),
+                                );
                             } else {
                                 startUpload();
                             }